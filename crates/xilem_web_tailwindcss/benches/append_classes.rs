@@ -0,0 +1,24 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use xilem_web_tailwindcss::{TailwindClasses, tw};
+
+/// A large base list of owned tokens, as built at runtime from a theme.
+fn base_classes() -> TailwindClasses {
+    (0..64)
+        .flat_map(|i| tw(format!("p-{i} hover:bg-slate-{i}")))
+        .collect()
+}
+
+fn append_base(c: &mut Criterion) {
+    let owned = base_classes();
+    let borrowed = tw!("rounded px-4 py-2 font-medium shadow-sm focus:ring-2");
+
+    c.bench_function("extend &TailwindClasses (owned tokens)", |b| {
+        b.iter(|| black_box(tw!(&owned, "bg-red-600")));
+    });
+    c.bench_function("extend &TailwindClasses (borrowed tokens)", |b| {
+        b.iter(|| black_box(tw!(&borrowed, "bg-red-600")));
+    });
+}
+
+criterion_group!(benches, append_base);
+criterion_main!(benches);
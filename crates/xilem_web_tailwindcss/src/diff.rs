@@ -0,0 +1,89 @@
+//! Class-level diffs for updating an element's `classList` in place.
+
+use std::collections::HashSet;
+
+use crate::TailwindClasses;
+
+/// The `classList` changes that turn one class list into another.
+///
+/// Both lists keep the order in which their tokens first appear, without
+/// duplicates or empty tokens, so applying a plan is deterministic.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassUpdatePlan {
+    pub to_add: TailwindClasses,
+    pub to_remove: TailwindClasses,
+}
+
+impl ClassUpdatePlan {
+    /// Whether the two lists hold the same set of classes.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.to_add.is_empty() && self.to_remove.is_empty()
+    }
+
+    /// Remove and add the classes on `element`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the browser rejects a token, e.g. one containing whitespace.
+    #[cfg(feature = "web_sys")]
+    pub fn apply_to(
+        &self,
+        element: &web_sys::Element,
+    ) -> Result<(), web_sys::wasm_bindgen::JsValue> {
+        let class_list = element.class_list();
+        for token in &self.to_remove {
+            class_list.remove_1(token)?;
+        }
+        for token in &self.to_add {
+            class_list.add_1(token)?;
+        }
+        Ok(())
+    }
+}
+
+/// The classes to add and remove when an element's classes change from `old`
+/// to `new`, computed as set differences.
+///
+/// ```
+/// use xilem_web_tailwindcss::{class_update_plan, tw};
+///
+/// let plan = class_update_plan(&tw!("p-4 bg-white flex"), &tw!("p-4 flex bg-black"));
+/// assert_eq!(plan.to_add, tw!("bg-black"));
+/// assert_eq!(plan.to_remove, tw!("bg-white"));
+/// ```
+#[must_use]
+pub fn class_update_plan(old: &TailwindClasses, new: &TailwindClasses) -> ClassUpdatePlan {
+    ClassUpdatePlan {
+        to_add: difference(new, old),
+        to_remove: difference(old, new),
+    }
+}
+
+/// Tokens of `from` missing in `other`, once each, in order.
+fn difference(from: &TailwindClasses, other: &TailwindClasses) -> TailwindClasses {
+    let mut seen: HashSet<&str> = other.iter().map(AsRef::as_ref).collect();
+    let mut tokens = Vec::new();
+    for token in from {
+        if !token.is_empty() && seen.insert(token) {
+            tokens.push(token.clone());
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::class_update_plan;
+    use crate::tw;
+
+    #[test]
+    fn plans_are_deduplicated_and_ordered() {
+        let old = tw!("a b b c");
+        let new = tw!("d c e d", crate::TwRaw(""));
+        let plan = class_update_plan(&old, &new);
+        assert_eq!(plan.to_add, tw!("d e"));
+        assert_eq!(plan.to_remove, tw!("a b"));
+        assert!(class_update_plan(&old, &tw!("c a b")).is_empty());
+    }
+}
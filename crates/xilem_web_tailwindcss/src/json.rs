@@ -0,0 +1,55 @@
+//! JSON array form of class lists, for tools that read and write them.
+
+use std::borrow::Cow;
+
+use crate::TailwindClasses;
+
+/// Serialize a class list as a JSON array of tokens.
+///
+/// Unlike the space-joined [`DisplayClasses`](crate::DisplayClasses) form,
+/// every token round-trips exactly, including tokens containing whitespace
+/// (see [`TwRaw`](crate::TwRaw)). Prefer the string form for HTML and
+/// templates, and the array for design tools that edit class lists.
+///
+/// ```
+/// use xilem_web_tailwindcss::{classes_from_json, classes_to_json, tw};
+///
+/// let json = classes_to_json(&tw!("p-4 flex"));
+/// assert_eq!(json, r#"["p-4","flex"]"#);
+/// assert_eq!(classes_from_json(&json).unwrap(), tw!("p-4 flex"));
+/// ```
+#[must_use]
+pub fn classes_to_json(classes: &TailwindClasses) -> String {
+    classes
+        .iter()
+        .map(|token| serde_json::Value::String(token.to_string()))
+        .collect::<serde_json::Value>()
+        .to_string()
+}
+
+/// Parse a JSON array of tokens into a class list, keeping every token as is.
+///
+/// # Errors
+///
+/// Fails if `json` is not an array of strings.
+pub fn classes_from_json(json: &str) -> Result<TailwindClasses, serde_json::Error> {
+    let tokens: Vec<String> = serde_json::from_str(json)?;
+    Ok(tokens.into_iter().map(Cow::Owned).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classes_from_json, classes_to_json};
+    use crate::{TwRaw, tw};
+
+    #[test]
+    fn json_round_trips_unusual_tokens() {
+        let classes = tw!("p-4", TwRaw("content-['a b']"), "[&>*]:\"quoted\"");
+        let json = classes_to_json(&classes);
+        assert_eq!(json, r#"["p-4","content-['a b']","[&>*]:\"quoted\""]"#);
+        assert_eq!(classes_from_json(&json).unwrap(), classes);
+
+        assert!(classes_from_json(r#"{"class": "p-4"}"#).is_err());
+        assert!(classes_from_json("[1, 2]").is_err());
+    }
+}
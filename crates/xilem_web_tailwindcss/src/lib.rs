@@ -3,6 +3,101 @@
 //! The `tw!` macro splits whitespace into class tokens and returns a
 //! `TailwindClasses` list.
 //!
+//! `extend` and `override` compose existing class lists: override tokens
+//! replace any earlier token targeting the same utility group, so
+//! `tw!(extend button_classes(), override "bg-red-600")` swaps the button color
+//! while keeping everything else. `tw_merge!` applies the same rule to every
+//! argument, keeping only the last token of each group. `remove_variant`
+//! drops every token carrying a variant, e.g.
+//! `tw!(extend classes, remove_variant "dark")` strips all `dark:` classes
+//! when forcing a light theme.
+//!
+//! `if cond => "..." else => "..."` picks one of two inputs with a single
+//! evaluation of `cond`, e.g. `tw!(if dark => "bg-gray-900" else => "bg-white")`.
+//!
+//! `match value { pattern => input, ... }` appends the input of the arm that
+//! matches; arms may produce different input types, and literal arms stay
+//! borrowed.
+//!
+//! A leading `@cap(n)` pre-sizes the list for hot paths that build long lists,
+//! e.g. `tw!(@cap(32), "flex", if active => "ring-2", ...)`.
+//!
+//! `drain list` moves the tokens of a `ClassList` out of `list`, leaving it
+//! empty, where `extend &list` would clone them. A drained `TailwindClasses`
+//! is emptied too, but its tokens are split and copied like any `Cow`.
+//!
+//! `repeat n => |i| ...` appends one result per index in `0..n`, e.g.
+//! `tw!(repeat 3 => |i| format!("delay-[{}ms]", i * 100))` for stagger delays.
+//!
+//! `pick i => ["bg-red-500", "bg-green-500", theme.accent()]` appends only
+//! the element at index `i` and nothing when `i` is out of range. Only the
+//! chosen element is evaluated, and the elements may have different types.
+//!
+//! `scale "p" => n` appends `p-{n}`; with the `validate` feature, a literal
+//! `n` off the default spacing scale (`0`, `0.5`, `1`, ... `96`) is a compile
+//! error, e.g. `tw!(scale "gap" => 13)`. The feature also rejects string
+//! literal tokens that leave a built-in stem without a value, like `tw!("p-")`.
+//!
+//! `if cond => responsive { base: "grid-cols-1", md: "grid-cols-3" }` emits a
+//! responsive group only while `cond` holds. The keys are `base` and the
+//! [`Breakpoint`] names `sm`, `md`, `lg`, `xl` and `xl2`.
+//!
+//! `debug "outline outline-red-500"` keeps dev-only visual aids out of release
+//! builds, and `release "..."` does the inverse; both are resolved with
+//! `cfg!(debug_assertions)` at compile time.
+//!
+//! Adjacent literals (`tw!("px-4" "py-2")`) and `concat!(...)` arguments are
+//! split at compile time like any literal, so they stay borrowed.
+//!
+//! For a single conditional value, `bool::then_some` (or `bool::then` for a
+//! computed value) is the idiomatic alternative to an `if` arm, since any
+//! `Option` input is appended only when it is `Some`:
+//!
+//! ```
+//! use xilem_web_tailwindcss::{StrArray, tw};
+//!
+//! let active = true;
+//! let size = 4;
+//! let classes = tw!(
+//!     "btn",
+//!     active.then_some("ring-2 ring-blue-500"),
+//!     (size > 2).then(|| format!("p-{size}")),
+//! );
+//! assert_eq!(classes, tw!("btn ring-2 ring-blue-500 p-4"));
+//!
+//! // Filter a list of optional utilities with an iterator.
+//! let flags = [("underline", true), ("italic", false), ("font-bold", true)];
+//! let enabled = flags.iter().filter(|(_, on)| *on).map(|(class, _)| class);
+//! assert_eq!(tw!(StrArray(enabled)), tw!("underline font-bold"));
+//! ```
+//!
+//! Any expression whose type implements [`TwInput`] can be an argument,
+//! including method calls (also through trait objects) and field accesses,
+//! with or without an `if` guard:
+//!
+//! ```
+//! use xilem_web_tailwindcss::{TailwindClasses, tw};
+//!
+//! trait Styled {
+//!     fn classes(&self) -> TailwindClasses;
+//! }
+//!
+//! struct Card {
+//!     padding: &'static str,
+//! }
+//!
+//! impl Styled for Card {
+//!     fn classes(&self) -> TailwindClasses {
+//!         tw!("rounded", self.padding)
+//!     }
+//! }
+//!
+//! let card = Card { padding: "p-4" };
+//! let widget: &dyn Styled = &card;
+//! let classes = tw!(widget.classes(), if true => card.padding, "shadow");
+//! assert_eq!(classes, tw!("rounded p-4 p-4 shadow"));
+//! ```
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -20,6 +115,39 @@
 //! ```
 
 use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt;
+
+mod diff;
+#[cfg(feature = "serde")]
+mod json;
+mod merge;
+mod parse;
+mod set;
+mod tokenize;
+mod utility;
+mod validate;
+pub mod variants;
+
+pub use diff::{ClassUpdatePlan, class_update_plan};
+#[cfg(feature = "serde")]
+pub use json::{classes_from_json, classes_to_json};
+pub use merge::{dedup_classes, override_classes, tw_merge};
+pub use parse::{parse_class_attr, split_variants};
+pub use set::ClassSet;
+pub use tokenize::{BracketAware, TwTokenizer, Whitespace, tw_with};
+pub use utility::Utility;
+pub use validate::{filter_allowed, is_known_utility, validate_against};
+pub use variants::{
+    Breakpoint, prefix_all, remove_variant, responsive, strip_prefix, with_variant,
+};
+
+#[doc(hidden)]
+pub use merge::__tw_override_literal;
+#[doc(hidden)]
+pub use tokenize::{__tw_push_literal_with, __tw_push_str_with};
+#[doc(hidden)]
+pub use validate::{__TW_ARGUMENT_KEYWORDS, __tw_check_literal};
 
 /// A class token type compatible with `xilem_web::modifiers::ClassIter`.
 pub type CowStr = Cow<'static, str>;
@@ -28,6 +156,11 @@ pub type CowStr = Cow<'static, str>;
 pub type TailwindClasses = Vec<CowStr>;
 
 /// Inputs that can be appended to a Tailwind class list.
+///
+/// Append order is part of the contract: containers (`Vec`, arrays, slices,
+/// tuples, `Option`) append their items strictly left to right, depth first,
+/// and `tw!` appends its arguments in the order written. Conflict resolution
+/// that keeps the last token, like `override`, is therefore deterministic.
 pub trait TwInput {
     /// Append this input to an existing class list.
     fn append_to(self, classes: &mut TailwindClasses);
@@ -40,26 +173,235 @@ pub fn tw(input: impl TwInput) -> TailwindClasses {
     classes
 }
 
+/// [`tw`](fn@tw) under the name familiar from `clsx`/`classnames`.
+///
+/// ```
+/// use xilem_web_tailwindcss::{cx, tw, when};
+///
+/// assert_eq!(cx(["btn", "p-4"]), tw!("btn p-4"));
+/// assert_eq!(cx((when(false, "ring"), "flex")), tw!("flex"));
+/// ```
+pub fn cx(input: impl TwInput) -> TailwindClasses {
+    tw(input)
+}
+
+/// A `&'static str` class list that is split lazily and never allocates.
+///
+/// Returned by [`tw_static`] for the common single-literal case. With the
+/// `xilem_web` feature it implements `ClassIter`, so it can be passed to
+/// `.class()` directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StaticClasses(&'static str);
+
+impl StaticClasses {
+    /// Iterate over the whitespace-separated tokens.
+    pub fn iter(&self) -> impl Iterator<Item = &'static str> + use<> {
+        self.0.split_whitespace()
+    }
+}
+
+impl IntoIterator for StaticClasses {
+    type Item = CowStr;
+    type IntoIter = std::iter::Map<std::str::SplitWhitespace<'static>, fn(&'static str) -> CowStr>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.split_whitespace().map(Cow::Borrowed)
+    }
+}
+
+impl TwInput for StaticClasses {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        __tw_push_literal(classes, self.0);
+    }
+}
+
+#[cfg(feature = "xilem_web")]
+impl xilem_web::modifiers::ClassIter for StaticClasses {
+    fn class_iter(&self) -> impl Iterator<Item = CowStr> {
+        self.into_iter()
+    }
+}
+
+/// Split a static class string without building a `TailwindClasses` list.
+///
+/// ```
+/// use xilem_web_tailwindcss::tw_static;
+///
+/// let classes = tw_static("p-4 flex");
+/// assert_eq!(classes.iter().collect::<Vec<_>>(), ["p-4", "flex"]);
+/// ```
+#[must_use]
+pub fn tw_static(classes: &'static str) -> StaticClasses {
+    StaticClasses(classes)
+}
+
 #[doc(hidden)]
 pub fn __tw_push_literal(classes: &mut TailwindClasses, input: &'static str) {
-    classes.extend(input.split_whitespace().map(Cow::Borrowed));
+    __tw_push_literal_with(&Whitespace, classes, input);
 }
 
 #[doc(hidden)]
 pub fn __tw_push_str(classes: &mut TailwindClasses, input: &str) {
-    classes.extend(
-        input
-            .split_whitespace()
-            .map(|token| Cow::Owned(token.to_string())),
+    __tw_push_str_with(&Whitespace, classes, input);
+}
+
+#[doc(hidden)]
+pub fn __tw_repeat<T: TwInput>(
+    classes: &mut TailwindClasses,
+    count: usize,
+    mut f: impl FnMut(usize) -> T,
+) {
+    for index in 0..count {
+        f(index).append_to(classes);
+    }
+}
+
+/// The number of whitespace-separated tokens in `value`, for `tw_count!`.
+///
+/// Only ASCII whitespace separates tokens here; class literals rarely
+/// contain any other kind.
+#[doc(hidden)]
+#[must_use]
+pub const fn __tw_count_tokens(value: &str) -> usize {
+    let bytes = value.as_bytes();
+    let mut count = 0;
+    let mut in_token = false;
+    let mut index = 0;
+    while index < bytes.len() {
+        let space = bytes[index].is_ascii_whitespace();
+        if !space && !in_token {
+            count += 1;
+        }
+        in_token = !space;
+        index += 1;
+    }
+    count
+}
+
+/// The whitespace-separated tokens of `values`, in order, for `tw_arr!`.
+/// `N` must be their total count; see [`__tw_count_tokens`].
+#[doc(hidden)]
+#[must_use]
+pub const fn __tw_tokens<const N: usize>(values: &[&'static str]) -> [&'static str; N] {
+    let mut tokens = [""; N];
+    let mut count = 0;
+    let mut value = 0;
+    while value < values.len() {
+        let bytes = values[value].as_bytes();
+        let mut index = 0;
+        while index < bytes.len() {
+            while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+                index += 1;
+            }
+            let start = index;
+            while index < bytes.len() && !bytes[index].is_ascii_whitespace() {
+                index += 1;
+            }
+            if index > start {
+                let (_, rest) = bytes.split_at(start);
+                let (token, _) = rest.split_at(index - start);
+                // ASCII whitespace always sits on a char boundary.
+                tokens[count] = match core::str::from_utf8(token) {
+                    Ok(token) => token,
+                    Err(_) => panic!("token is not on a char boundary"),
+                };
+                count += 1;
+            }
+        }
+        value += 1;
+    }
+    tokens
+}
+
+/// Tailwind's default spacing scale, which `scale` arms check literals against.
+const SPACING_SCALE: [f64; 34] = [
+    0.0, 0.5, 1.0, 1.5, 2.0, 2.5, 3.0, 3.5, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 14.0,
+    16.0, 20.0, 24.0, 28.0, 32.0, 36.0, 40.0, 44.0, 48.0, 52.0, 56.0, 60.0, 64.0, 72.0, 80.0, 96.0,
+];
+
+// The scale values are exact binary fractions, so `==` is reliable.
+#[allow(clippy::float_cmp)]
+const fn on_spacing_scale(value: f64) -> bool {
+    let value = if value < 0.0 { -value } else { value };
+    let mut index = 0;
+    while index < SPACING_SCALE.len() {
+        if SPACING_SCALE[index] == value {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+/// With the `validate` feature, fails const evaluation (and so compilation)
+/// for a literal that is off the default spacing scale.
+#[doc(hidden)]
+pub const fn __tw_check_scale(value: f64) {
+    assert!(
+        !cfg!(feature = "validate") || on_spacing_scale(value),
+        "`scale` value is not on Tailwind's default spacing scale"
     );
 }
 
-impl TwInput for CowStr {
+/// Push `{prefix}-{value}`, moving a negative sign to the front (`-m-4`).
+#[doc(hidden)]
+pub fn __tw_scale(classes: &mut TailwindClasses, prefix: &str, value: impl fmt::Display) {
+    let value = value.to_string();
+    let token = match value.strip_prefix('-') {
+        Some(magnitude) => format!("-{prefix}-{magnitude}"),
+        None => format!("{prefix}-{value}"),
+    };
+    classes.push(Cow::Owned(token));
+}
+
+/// A `Cow` of any lifetime, such as one built from a local buffer. An `Owned`
+/// string is moved in like a [`String`]. A `Borrowed` one is split on
+/// whitespace and copied, since a non-`'static` borrow cannot be kept in the
+/// class list; this includes `'static` borrows and the tokens of a
+/// [`TailwindClasses`] passed by value, so pass `&classes` to keep those as
+/// they are.
+impl TwInput for Cow<'_, str> {
     fn append_to(self, classes: &mut TailwindClasses) {
-        classes.push(self);
+        match self {
+            Cow::Borrowed(input) => __tw_push_str(classes, input),
+            Cow::Owned(input) => input.append_to(classes),
+        }
+    }
+}
+
+/// Split and copied like a borrowed [`Cow`] passed by value.
+impl TwInput for &Cow<'_, str> {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        __tw_push_str(classes, self);
     }
 }
 
+#[doc(hidden)]
+pub fn __tw_split_commas(classes: TailwindClasses) -> TailwindClasses {
+    let mut split = Vec::with_capacity(classes.len());
+    for token in classes {
+        if !token.contains(',') {
+            split.push(token);
+            continue;
+        }
+        match token {
+            Cow::Borrowed(token) => split.extend(
+                token
+                    .split(',')
+                    .filter(|part| !part.is_empty())
+                    .map(Cow::Borrowed),
+            ),
+            Cow::Owned(token) => split.extend(
+                token
+                    .split(',')
+                    .filter(|part| !part.is_empty())
+                    .map(|part| Cow::Owned(part.to_string())),
+            ),
+        }
+    }
+    split
+}
+
 impl TwInput for &TailwindClasses {
     fn append_to(self, classes: &mut TailwindClasses) {
         classes.extend(self.iter().cloned());
@@ -72,7 +414,24 @@ impl TwInput for &str {
     }
 }
 
+/// A string that is already a single token is moved in without copying.
 impl TwInput for String {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        if !self.is_empty() && !self.contains(char::is_whitespace) {
+            classes.push(Cow::Owned(self));
+        } else {
+            __tw_push_str(classes, &self);
+        }
+    }
+}
+
+impl TwInput for &String {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        self.as_str().append_to(classes);
+    }
+}
+
+impl TwInput for Box<str> {
     fn append_to(self, classes: &mut TailwindClasses) {
         __tw_push_str(classes, &self);
     }
@@ -86,6 +445,18 @@ impl<T: TwInput> TwInput for Option<T> {
     }
 }
 
+/// Appends whichever side is present, so branches that produce different
+/// input types can be unified without boxing.
+#[cfg(feature = "either")]
+impl<L: TwInput, R: TwInput> TwInput for either::Either<L, R> {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        match self {
+            either::Either::Left(left) => left.append_to(classes),
+            either::Either::Right(right) => right.append_to(classes),
+        }
+    }
+}
+
 impl<T: TwInput> TwInput for Vec<T> {
     fn append_to(self, classes: &mut TailwindClasses) {
         for value in self {
@@ -102,11 +473,325 @@ impl<T: TwInput, const N: usize> TwInput for [T; N] {
     }
 }
 
+/// An owned class list built without the macro.
+///
+/// Splits like [`tw`](fn@tw) and derefs to [`TailwindClasses`], so it works
+/// wherever a class list is expected:
+///
+/// ```
+/// use xilem_web_tailwindcss::{ClassList, tw};
+///
+/// let classes = ClassList::from_tailwind("p-4 flex");
+/// assert_eq!(classes.len(), 2);
+/// assert_eq!(*classes, tw!("p-4 flex"));
+/// assert_eq!(ClassList::from(String::from("p-4  flex")), classes);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ClassList(pub TailwindClasses);
+
+impl ClassList {
+    /// Split `classes` on whitespace into owned tokens.
+    #[must_use]
+    pub fn from_tailwind(classes: &str) -> Self {
+        Self(tw(classes))
+    }
+
+    /// Take the tokens out without copying them.
+    #[must_use]
+    pub fn into_classes(self) -> TailwindClasses {
+        self.0
+    }
+}
+
+impl From<&str> for ClassList {
+    fn from(classes: &str) -> Self {
+        Self::from_tailwind(classes)
+    }
+}
+
+impl From<String> for ClassList {
+    fn from(classes: String) -> Self {
+        Self(tw(classes))
+    }
+}
+
+impl From<TailwindClasses> for ClassList {
+    fn from(classes: TailwindClasses) -> Self {
+        Self(classes)
+    }
+}
+
+impl From<ClassList> for TailwindClasses {
+    fn from(classes: ClassList) -> Self {
+        classes.0
+    }
+}
+
+impl std::ops::Deref for ClassList {
+    type Target = TailwindClasses;
+
+    fn deref(&self) -> &TailwindClasses {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for ClassList {
+    fn deref_mut(&mut self) -> &mut TailwindClasses {
+        &mut self.0
+    }
+}
+
+impl PartialEq<TailwindClasses> for ClassList {
+    fn eq(&self, other: &TailwindClasses) -> bool {
+        self.0 == *other
+    }
+}
+
+/// Moved in whole when `classes` has no allocation yet, so an `@cap(n)`
+/// hint is never thrown away.
+impl TwInput for ClassList {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        if classes.capacity() == 0 {
+            *classes = self.0;
+        } else {
+            classes.extend(self.0);
+        }
+    }
+}
+
+impl TwInput for &ClassList {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        (&self.0).append_to(classes);
+    }
+}
+
+#[cfg(feature = "xilem_web")]
+impl xilem_web::modifiers::ClassIter for ClassList {
+    fn class_iter(&self) -> impl Iterator<Item = CowStr> {
+        self.0.class_iter()
+    }
+}
+
+/// Formats a class list as a space-separated string, e.g. for templates.
+///
+/// ```
+/// use xilem_web_tailwindcss::{DisplayClasses, tw};
+///
+/// let classes = tw!("p-4 flex");
+/// assert_eq!(format!(r#"class="{}""#, DisplayClasses(&classes)), r#"class="p-4 flex""#);
+/// ```
+///
+/// Empty tokens are skipped, so there are never doubled spaces.
+#[derive(Debug, Clone, Copy)]
+pub struct DisplayClasses<'a>(pub &'a TailwindClasses);
+
+impl fmt::Display for DisplayClasses<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tokens = self.0.iter().filter(|token| !token.is_empty());
+        if let Some(first) = tokens.next() {
+            f.write_str(first)?;
+            for token in tokens {
+                f.write_str(" ")?;
+                f.write_str(token)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The class list as one space-separated string, for attributes set by hand
+/// or logging; see [`DisplayClasses`].
+///
+/// ```
+/// use xilem_web_tailwindcss::{TailwindClasses, join_classes, tw};
+///
+/// assert_eq!(join_classes(&tw!("p-4 flex")), "p-4 flex");
+/// assert_eq!(join_classes(&TailwindClasses::new()), "");
+/// ```
+#[must_use]
+pub fn join_classes(classes: &TailwindClasses) -> String {
+    DisplayClasses(classes).to_string()
+}
+
+/// Add `token` to `classes` when `on` is true, or remove every copy of it
+/// when it is false.
+///
+/// An existing copy is kept rather than pushed again. Tokens are compared by
+/// content, so owned copies are removed too.
+///
+/// ```
+/// use xilem_web_tailwindcss::{toggle, tw};
+///
+/// let mut classes = tw!("p-4 hidden", String::from("hidden"));
+/// toggle(&mut classes, "hidden", false);
+/// assert_eq!(classes, tw!("p-4"));
+/// toggle(&mut classes, "hidden", true);
+/// assert_eq!(classes, tw!("p-4 hidden"));
+/// ```
+pub fn toggle(classes: &mut TailwindClasses, token: &'static str, on: bool) {
+    if !on {
+        classes.retain(|existing| existing != token);
+    } else if !classes.iter().any(|existing| existing == token) {
+        classes.push(Cow::Borrowed(token));
+    }
+}
+
+/// Pushes its string as a single token, without splitting on whitespace.
+///
+/// An escape hatch for the rare class that really contains a space:
+///
+/// ```
+/// use xilem_web_tailwindcss::{TwRaw, tw};
+///
+/// let classes = tw!("p-4", TwRaw("content-['hello world']"));
+/// assert_eq!(classes[1], "content-['hello world']");
+/// ```
+///
+/// Empty strings are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TwRaw<'a>(pub &'a str);
+
+impl TwInput for TwRaw<'_> {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        if !self.0.is_empty() {
+            classes.push(Cow::Owned(self.0.to_string()));
+        }
+    }
+}
+
+/// Appends its value only when the condition is true.
+///
+/// A conditional class as a value rather than an `if` arm, so conditional
+/// sets can be built and stored as data:
+///
+/// ```
+/// use xilem_web_tailwindcss::{When, tw, when};
+///
+/// let (selected, disabled) = (true, false);
+/// let extras: Vec<When<&str>> = vec![
+///     when(selected, "ring-2"),
+///     When::new(disabled, "opacity-50"),
+/// ];
+/// assert_eq!(tw!("btn", extras), tw!("btn ring-2"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct When<T>(pub bool, pub T);
+
+impl<T> When<T> {
+    /// Pair `value` with the condition that decides whether it is appended.
+    #[must_use]
+    pub const fn new(condition: bool, value: T) -> Self {
+        Self(condition, value)
+    }
+}
+
+/// Shorthand for [`When::new`].
+#[must_use]
+pub const fn when<T>(condition: bool, value: T) -> When<T> {
+    When(condition, value)
+}
+
+impl<T: TwInput> TwInput for When<T> {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        if self.0 {
+            self.1.append_to(classes);
+        }
+    }
+}
+
+/// The `clsx` object form: keys whose value is `true` are appended, in key
+/// order.
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use xilem_web_tailwindcss::{cx, tw};
+///
+/// let toggles = BTreeMap::from([("font-bold", true), ("italic", false)]);
+/// assert_eq!(cx!("btn", toggles), tw!("btn font-bold"));
+/// ```
+impl<K: TwInput> TwInput for BTreeMap<K, bool> {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        for (key, enabled) in self {
+            if enabled {
+                key.append_to(classes);
+            }
+        }
+    }
+}
+
+macro_rules! impl_tw_input_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: TwInput),+> TwInput for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn append_to(self, classes: &mut TailwindClasses) {
+                let ($($name,)+) = self;
+                $($name.append_to(classes);)+
+            }
+        }
+    };
+}
+
+impl_tw_input_tuple!(A);
+impl_tw_input_tuple!(A, B);
+impl_tw_input_tuple!(A, B, C);
+impl_tw_input_tuple!(A, B, C, D);
+impl_tw_input_tuple!(A, B, C, D, E);
+impl_tw_input_tuple!(A, B, C, D, E, F);
+impl_tw_input_tuple!(A, B, C, D, E, F, G);
+impl_tw_input_tuple!(A, B, C, D, E, F, G, H);
+impl_tw_input_tuple!(A, B, C, D, E, F, G, H, I);
+impl_tw_input_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_tw_input_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_tw_input_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+/// Tokenizes a slice of any string-like items, e.g. `&[&str]` or `&[String]`.
+impl<S: AsRef<str>> TwInput for &[S] {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        for value in self {
+            __tw_push_str(classes, value.as_ref());
+        }
+    }
+}
+
+/// Adapts any iterable of string-like items into a [`TwInput`].
+///
+/// `Vec<T>` and `[T; N]` require `T: TwInput`; wrap collections of other
+/// `AsRef<str>` types, such as `Vec<&String>` or `[Box<str>; 2]`, instead.
+///
+/// ```
+/// use xilem_web_tailwindcss::{StrArray, tw};
+///
+/// let names = vec![String::from("p-4"), String::from("flex")];
+/// assert_eq!(tw!(StrArray(names.iter())), tw!("p-4 flex"));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrArray<I>(pub I);
+
+impl<I> TwInput for StrArray<I>
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    fn append_to(self, classes: &mut TailwindClasses) {
+        for value in self.0 {
+            __tw_push_str(classes, value.as_ref());
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! tw {
     () => {
         ::std::vec::Vec::new()
     };
+    (@cap($capacity:expr) $(,)?) => {
+        ::std::vec::Vec::with_capacity($capacity)
+    };
+    (@cap($capacity:expr), $($rest:tt)+) => {{
+        let mut classes = ::std::vec::Vec::with_capacity($capacity);
+        $crate::__tw_internal!(@append classes; $($rest)+);
+        classes
+    }};
     ($($rest:tt)+) => {{
         let mut classes = ::std::vec::Vec::new();
         $crate::__tw_internal!(@append classes; $($rest)+);
@@ -114,19 +799,418 @@ macro_rules! tw {
     }};
 }
 
+/// `tw!` under the name familiar from `clsx`/`classnames`; every `tw!` arm
+/// works the same.
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use xilem_web_tailwindcss::{cx, tw, when};
+///
+/// let active = true;
+/// let classes = cx!(
+///     "btn",
+///     [when(active, "ring-2"), when(!active, "opacity-50")],
+///     BTreeMap::from([("shadow", active)]),
+///     if active => "bg-blue-600",
+/// );
+/// assert_eq!(classes, tw!("btn ring-2 shadow bg-blue-600"));
+/// ```
+#[macro_export]
+macro_rules! cx {
+    ($($rest:tt)*) => {
+        $crate::tw!($($rest)*)
+    };
+}
+
+/// `tw!` followed by [`tw_merge`](fn@tw_merge): conflicting utilities keep
+/// only the last one written.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_merge};
+///
+/// let compact = true;
+/// let classes = tw_merge!("px-4 py-2 text-sm", if compact => "px-2 text-xs");
+/// assert_eq!(classes, tw!("py-2 px-2 text-xs"));
+/// ```
+#[macro_export]
+macro_rules! tw_merge {
+    ($($rest:tt)*) => {
+        $crate::tw_merge($crate::tw!($($rest)*))
+    };
+}
+
+/// `tw!` followed by [`dedup_classes`]: repeated tokens are dropped, keeping
+/// the first occurrence.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_dedup};
+///
+/// let centered = true;
+/// let classes = tw_dedup!("flex items-center", if centered => "flex justify-center");
+/// assert_eq!(classes, tw!("flex items-center justify-center"));
+/// ```
+#[macro_export]
+macro_rules! tw_dedup {
+    ($($rest:tt)*) => {{
+        let mut classes = $crate::tw!($($rest)*);
+        $crate::dedup_classes(&mut classes);
+        classes
+    }};
+}
+
+/// Like `tw!`, but also splits on commas for class strings pasted from tools
+/// that separate classes with `,`.
+///
+/// Do not use this with arbitrary values that contain commas, such as
+/// `grid-cols-[repeat(3,1fr)]`: they are split into broken tokens. `tw!`
+/// only splits on whitespace and keeps them intact.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_csv};
+///
+/// assert_eq!(tw_csv!("p-4,flex, gap-2"), tw!("p-4 flex gap-2"));
+/// ```
+#[macro_export]
+macro_rules! tw_csv {
+    ($($rest:tt)*) => {
+        $crate::__tw_split_commas($crate::tw!($($rest)*))
+    };
+}
+
+/// Mark a named `&'static str` constant so `tw!` keeps its tokens borrowed.
+///
+/// A bare identifier is an expression to `tw!`, so its tokens would be
+/// copied into owned strings; `tw_lit!(NAME)` takes the same borrowed path as
+/// a string literal. The constant must be a `&'static str`.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use xilem_web_tailwindcss::{tw, tw_lit};
+///
+/// const CARD: &str = "rounded shadow";
+///
+/// let classes = tw!(tw_lit!(CARD), "p-4");
+/// assert!(matches!(classes[0], Cow::Borrowed("rounded")));
+/// ```
+#[macro_export]
+macro_rules! tw_lit {
+    ($constant:path) => {{
+        let classes: &'static str = $constant;
+        $crate::tw_static(classes)
+    }};
+}
+
+/// The number of tokens `tw!` would produce for the same string literals, as
+/// a constant expression.
+///
+/// Only literals are accepted; conditional and expression arguments have no
+/// count known at compile time.
+///
+/// ```
+/// use xilem_web_tailwindcss::tw_count;
+///
+/// const _: () = assert!(tw_count!("p-4 flex gap-2") == 3);
+/// assert_eq!(tw_count!("rounded", "  shadow p-4 "), 3);
+/// ```
+#[macro_export]
+macro_rules! tw_count {
+    ($($value:literal),* $(,)?) => {
+        0_usize $(+ $crate::__tw_count_tokens($value))*
+    };
+    ($($other:tt)*) => {
+        ::core::compile_error!("`tw_count!` only accepts string literals")
+    };
+}
+
+/// String literals split into a `[&'static str; N]` at compile time, for
+/// static class lists that never touch the heap.
+///
+/// Like [`tw_count!`], only literals are accepted. The array is a
+/// [`TwInput`] like any other array.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_arr};
+///
+/// const CARD: [&str; 3] = tw_arr!("rounded shadow", "p-4");
+/// assert_eq!(CARD, ["rounded", "shadow", "p-4"]);
+/// assert_eq!(tw!(CARD, "flex"), tw!("rounded shadow p-4 flex"));
+/// ```
+#[macro_export]
+macro_rules! tw_arr {
+    ($($value:literal),* $(,)?) => {{
+        const TOKENS: [&str; $crate::tw_count!($($value),*)] = $crate::__tw_tokens(&[$($value),*]);
+        TOKENS
+    }};
+    ($($other:tt)*) => {
+        ::core::compile_error!("`tw_arr!` only accepts string literals")
+    };
+}
+
+/// The class attribute string for the same arguments as `tw!`, as a single
+/// [`CowStr`].
+///
+/// When every argument is a string literal, they are joined with spaces at
+/// compile time into a borrowed `&'static str` and nothing is allocated. Any
+/// other argument builds the list with `tw!` and joins it like
+/// [`join_classes`]. The result is one attribute value, not a list of tokens,
+/// so set it as the `class` attribute rather than passing it to `.class()`.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use xilem_web_tailwindcss::tw_classes;
+///
+/// let card = tw_classes!("rounded shadow", "p-4");
+/// assert!(matches!(card, Cow::Borrowed("rounded shadow p-4")));
+///
+/// let active = true;
+/// assert_eq!(tw_classes!("p-4", if active => "ring-2"), "p-4 ring-2");
+/// ```
+#[macro_export]
+macro_rules! tw_classes {
+    () => {
+        $crate::CowStr::Borrowed("")
+    };
+    ($first:literal $(, $rest:literal)* $(,)?) => {{
+        const _: () = $crate::__tw_check_literal($first);
+        $(const _: () = $crate::__tw_check_literal($rest);)*
+        $crate::CowStr::Borrowed(::core::concat!($first $(, " ", $rest)*))
+    }};
+    ($($rest:tt)+) => {
+        $crate::CowStr::Owned($crate::join_classes(&$crate::tw!($($rest)+)))
+    };
+}
+
+/// Like `tw!`, but splits every argument with the given [`TwTokenizer`].
+///
+/// Arguments are string literals, which stay borrowed, or expressions
+/// implementing `AsRef<str>`.
+///
+/// ```
+/// use xilem_web_tailwindcss::{BracketAware, tw_with};
+///
+/// let classes = tw_with!(BracketAware, "p-4 content-['a b']");
+/// assert_eq!(classes, ["p-4", "content-['a b']"]);
+/// ```
+#[macro_export]
+macro_rules! tw_with {
+    ($tokenizer:expr $(,)?) => {
+        $crate::TailwindClasses::new()
+    };
+    ($tokenizer:expr, $($rest:tt)+) => {{
+        let tokenizer = &$tokenizer;
+        let mut classes = $crate::TailwindClasses::new();
+        $crate::__tw_internal!(@with tokenizer, classes; $($rest)+);
+        classes
+    }};
+}
+
+/// A semantic component class backed by Tailwind utilities.
+///
+/// Evaluates to a class list holding just the component name. The CLI scans
+/// `tw_component!` calls during `build`/`watch`/`dev` and writes a
+/// `components.css` next to the input with `.btn { @apply ...; }` rules, which
+/// the input CSS should `@import`. All arguments must be string literals so
+/// the CLI can read them.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_component};
+///
+/// let button = tw_component!("btn", "px-4 py-2 rounded bg-blue-600");
+/// assert_eq!(button, tw!("btn"));
+/// ```
+#[macro_export]
+macro_rules! tw_component {
+    ($name:literal, $($classes:literal),+ $(,)?) => {{
+        let _: &[&str] = &[$($classes),+];
+        $(const _: () = $crate::__tw_check_literal($classes);)+
+        $crate::tw!($name)
+    }};
+}
+
+/// Like `tw!`, with every token prefixed by a [`Breakpoint`] variant.
+///
+/// ```
+/// use xilem_web_tailwindcss::{Breakpoint, tw, tw_at};
+///
+/// assert_eq!(tw_at!(Breakpoint::Md, "p-4 flex"), tw!("md:p-4 md:flex"));
+/// ```
+#[macro_export]
+macro_rules! tw_at {
+    ($breakpoint:expr, $($rest:tt)*) => {
+        $crate::responsive($breakpoint, $crate::tw!($($rest)*))
+    };
+}
+
+/// Base classes followed by `Breakpoint => classes` pairs.
+///
+/// ```
+/// use xilem_web_tailwindcss::{Breakpoint, tw, tw_responsive};
+///
+/// let classes = tw_responsive!("grid-cols-1", Breakpoint::Md => "grid-cols-2");
+/// assert_eq!(classes, tw!("grid-cols-1 md:grid-cols-2"));
+/// ```
+#[macro_export]
+macro_rules! tw_responsive {
+    ($base:expr $(, $breakpoint:expr => $value:expr)* $(,)?) => {{
+        let mut classes = $crate::tw($base);
+        $(classes.extend($crate::responsive($breakpoint, $value));)*
+        classes
+    }};
+}
+
+/// Classes behind a `data-[...]` attribute variant.
+///
+/// `tw_data!(name, active, ...)` emits `data-[name]:` tokens only while
+/// `active` is true. `tw_data!(name => ...)` always emits them and leaves it to
+/// the element's `data-*` attribute to switch them on.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_data};
+///
+/// let open = true;
+/// assert_eq!(tw_data!("open", open, "flex p-4"), tw!("data-[open]:flex data-[open]:p-4"));
+/// assert_eq!(tw_data!("open" => "flex"), tw!("data-[open]:flex"));
+/// ```
+#[macro_export]
+macro_rules! tw_data {
+    ($name:expr => $($rest:tt)*) => {
+        $crate::with_variant(&::std::format!("data-[{}]", $name), $crate::tw!($($rest)*))
+    };
+    ($name:expr, $active:expr, $($rest:tt)*) => {
+        if $active {
+            $crate::tw_data!($name => $($rest)*)
+        } else {
+            $crate::TailwindClasses::new()
+        }
+    };
+}
+
+/// Classes behind an `aria-[...]` attribute variant; see [`tw_data!`].
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_aria};
+///
+/// let classes = tw_aria!("expanded=true" => "bg-blue-600");
+/// assert_eq!(classes, tw!("aria-[expanded=true]:bg-blue-600"));
+/// ```
+#[macro_export]
+macro_rules! tw_aria {
+    ($name:expr => $($rest:tt)*) => {
+        $crate::with_variant(&::std::format!("aria-[{}]", $name), $crate::tw!($($rest)*))
+    };
+    ($name:expr, $active:expr, $($rest:tt)*) => {
+        if $active {
+            $crate::tw_aria!($name => $($rest)*)
+        } else {
+            $crate::TailwindClasses::new()
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __tw_internal {
+    (@with $tokenizer:ident, $classes:ident; $(,)?) => {};
+    (@pick $classes:ident, $index:ident, $position:expr; ) => {
+        let _ = $index;
+    };
+    (@pick $classes:ident, $index:ident, $position:expr; $value:expr $(, $more:expr)*) => {
+        if $index == $position {
+            $crate::TwInput::append_to($value, &mut $classes);
+        } else {
+            $crate::__tw_internal!(@pick $classes, $index, $position + 1; $($more),*);
+        }
+    };
+    (@push $classes:ident, $value:expr) => {{
+        const _: () = $crate::__tw_check_literal($value);
+        $crate::__tw_push_literal(&mut $classes, $value);
+    }};
+    (@branch $classes:ident; $value:literal) => {
+        $crate::__tw_internal!(@push $classes, $value)
+    };
+    (@branch $classes:ident; $value:expr) => {
+        $crate::TwInput::append_to($value, &mut $classes)
+    };
+    (@if_else $classes:ident, $cond:expr, [$($then:tt)+]; else => $else:literal $(, $($rest:tt)*)?) => {{
+        if $cond {
+            $crate::__tw_internal!(@branch $classes; $($then)+);
+        } else {
+            $crate::__tw_internal!(@push $classes, $else);
+        }
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@if_else $classes:ident, $cond:expr, [$($then:tt)+]; else => $else:expr $(, $($rest:tt)*)?) => {{
+        if $cond {
+            $crate::__tw_internal!(@branch $classes; $($then)+);
+        } else {
+            $crate::TwInput::append_to($else, &mut $classes);
+        }
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@if_else $classes:ident, $cond:expr, [$($then:tt)*]; $next:tt $($rest:tt)*) => {
+        $crate::__tw_internal!(@if_else $classes, $cond, [$($then)* $next]; $($rest)*)
+    };
+    (@match $classes:ident, [$($scrutinee:tt)+]; { $($pat:pat $(if $guard:expr)? => $value:literal),* $(,)? } $(, $($rest:tt)*)?) => {{
+        match $($scrutinee)+ {
+            $($pat $(if $guard)? => $crate::__tw_internal!(@push $classes, $value),)*
+        }
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@match $classes:ident, [$($scrutinee:tt)+]; { $($pat:pat $(if $guard:expr)? => $value:expr),* $(,)? } $(, $($rest:tt)*)?) => {{
+        match $($scrutinee)+ {
+            $($pat $(if $guard)? => $crate::TwInput::append_to($value, &mut $classes),)*
+        }
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@match $classes:ident, [$($scrutinee:tt)*]; $next:tt $($rest:tt)*) => {
+        $crate::__tw_internal!(@match $classes, [$($scrutinee)* $next]; $($rest)*)
+    };
+    (@responsive $classes:ident; base => $value:expr) => {
+        $crate::TwInput::append_to($value, &mut $classes)
+    };
+    (@responsive $classes:ident; sm => $value:expr) => {
+        $classes.extend($crate::responsive($crate::Breakpoint::Sm, $value))
+    };
+    (@responsive $classes:ident; md => $value:expr) => {
+        $classes.extend($crate::responsive($crate::Breakpoint::Md, $value))
+    };
+    (@responsive $classes:ident; lg => $value:expr) => {
+        $classes.extend($crate::responsive($crate::Breakpoint::Lg, $value))
+    };
+    (@responsive $classes:ident; xl => $value:expr) => {
+        $classes.extend($crate::responsive($crate::Breakpoint::Xl, $value))
+    };
+    (@responsive $classes:ident; xl2 => $value:expr) => {
+        $classes.extend($crate::responsive($crate::Breakpoint::Xl2, $value))
+    };
+    (@with $tokenizer:ident, $classes:ident; concat!($($parts:tt)*) $(, $($rest:tt)*)?) => {{
+        $crate::__tw_push_literal_with($tokenizer, &mut $classes, ::core::concat!($($parts)*));
+        $crate::__tw_internal!(@with $tokenizer, $classes; $($($rest)*)?);
+    }};
+    (@with $tokenizer:ident, $classes:ident; $value:literal $(, $($rest:tt)*)?) => {{
+        $crate::__tw_push_literal_with($tokenizer, &mut $classes, $value);
+        $crate::__tw_internal!(@with $tokenizer, $classes; $($($rest)*)?);
+    }};
+    (@with $tokenizer:ident, $classes:ident; $value:expr $(, $($rest:tt)*)?) => {{
+        $crate::__tw_push_str_with($tokenizer, &mut $classes, ::std::convert::AsRef::<str>::as_ref(&$value));
+        $crate::__tw_internal!(@with $tokenizer, $classes; $($($rest)*)?);
+    }};
     (@append $classes:ident; ) => {};
+    (@append $classes:ident; if $cond:expr => responsive { $($key:ident : $value:expr),* $(,)? } $(, $($rest:tt)*)?) => {{
+        if $cond {
+            $($crate::__tw_internal!(@responsive $classes; $key => $value);)*
+        }
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
     (@append $classes:ident; if $cond:expr => $value:literal , $($rest:tt)*) => {{
         if $cond {
-            $crate::__tw_push_literal(&mut $classes, $value);
+            $crate::__tw_internal!(@push $classes, $value);
         }
         $crate::__tw_internal!(@append $classes; $($rest)*);
     }};
     (@append $classes:ident; if $cond:expr => $value:literal) => {{
         if $cond {
-            $crate::__tw_push_literal(&mut $classes, $value);
+            $crate::__tw_internal!(@push $classes, $value);
         }
     }};
     (@append $classes:ident; if $cond:expr => $value:expr , $($rest:tt)*) => {{
@@ -140,12 +1224,109 @@ macro_rules! __tw_internal {
             $crate::TwInput::append_to($value, &mut $classes);
         }
     }};
+    (@append $classes:ident; match $($rest:tt)+) => {
+        $crate::__tw_internal!(@match $classes, []; $($rest)+)
+    };
+    (@append $classes:ident; if $cond:expr => $($rest:tt)+) => {
+        $crate::__tw_internal!(@if_else $classes, $cond, []; $($rest)+)
+    };
+    (@append $classes:ident; extend $value:expr , $($rest:tt)*) => {{
+        $crate::TwInput::append_to($value, &mut $classes);
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; extend $value:expr) => {{
+        $crate::TwInput::append_to($value, &mut $classes);
+    }};
+    (@append $classes:ident; drain $value:expr , $($rest:tt)*) => {{
+        $crate::TwInput::append_to(::core::mem::take(&mut $value), &mut $classes);
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; drain $value:expr) => {{
+        $crate::TwInput::append_to(::core::mem::take(&mut $value), &mut $classes);
+    }};
+    (@append $classes:ident; override $value:literal , $($rest:tt)*) => {{
+        const _: () = $crate::__tw_check_literal($value);
+        $crate::__tw_override_literal(&mut $classes, $value);
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; override $value:literal) => {{
+        const _: () = $crate::__tw_check_literal($value);
+        $crate::__tw_override_literal(&mut $classes, $value);
+    }};
+    (@append $classes:ident; override $value:expr , $($rest:tt)*) => {{
+        $crate::override_classes(&mut $classes, $value);
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; override $value:expr) => {{
+        $crate::override_classes(&mut $classes, $value);
+    }};
+    (@append $classes:ident; remove_variant $variant:expr , $($rest:tt)*) => {{
+        $crate::remove_variant(&mut $classes, $variant);
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; remove_variant $variant:expr) => {{
+        $crate::remove_variant(&mut $classes, $variant);
+    }};
+    (@append $classes:ident; repeat $count:expr => $f:expr , $($rest:tt)*) => {{
+        $crate::__tw_repeat(&mut $classes, $count, $f);
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; repeat $count:expr => $f:expr) => {{
+        $crate::__tw_repeat(&mut $classes, $count, $f);
+    }};
+    (@append $classes:ident; pick $index:expr => [$($value:expr),* $(,)?] $(, $($rest:tt)*)?) => {{
+        let index: usize = $index;
+        $crate::__tw_internal!(@pick $classes, index, 0_usize; $($value),*);
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@append $classes:ident; scale $prefix:literal => $value:literal $(, $($rest:tt)*)?) => {{
+        const _: () = $crate::__tw_check_scale($value as f64);
+        $crate::__tw_scale(&mut $classes, $prefix, $value);
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@append $classes:ident; scale $prefix:literal => $value:expr $(, $($rest:tt)*)?) => {{
+        $crate::__tw_scale(&mut $classes, $prefix, $value);
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@append $classes:ident; debug $value:literal , $($rest:tt)*) => {{
+        if cfg!(debug_assertions) {
+            $crate::__tw_internal!(@push $classes, $value);
+        }
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; debug $value:literal) => {{
+        if cfg!(debug_assertions) {
+            $crate::__tw_internal!(@push $classes, $value);
+        }
+    }};
+    (@append $classes:ident; release $value:literal , $($rest:tt)*) => {{
+        if !cfg!(debug_assertions) {
+            $crate::__tw_internal!(@push $classes, $value);
+        }
+        $crate::__tw_internal!(@append $classes; $($rest)*);
+    }};
+    (@append $classes:ident; release $value:literal) => {{
+        if !cfg!(debug_assertions) {
+            $crate::__tw_internal!(@push $classes, $value);
+        }
+    }};
+    // `concat!(...)` is a macro call rather than a literal token, so it would
+    // otherwise take the owned `expr` path below.
+    (@append $classes:ident; concat!($($parts:tt)*) $(, $($rest:tt)*)?) => {{
+        $crate::__tw_internal!(@push $classes, ::core::concat!($($parts)*));
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
+    (@append $classes:ident; $first:literal $($more:literal)+ $(, $($rest:tt)*)?) => {{
+        $crate::__tw_internal!(@push $classes, $first);
+        $($crate::__tw_internal!(@push $classes, $more);)+
+        $crate::__tw_internal!(@append $classes; $($($rest)*)?);
+    }};
     (@append $classes:ident; $value:literal , $($rest:tt)*) => {{
-        $crate::__tw_push_literal(&mut $classes, $value);
+        $crate::__tw_internal!(@push $classes, $value);
         $crate::__tw_internal!(@append $classes; $($rest)*);
     }};
     (@append $classes:ident; $value:literal) => {{
-        $crate::__tw_push_literal(&mut $classes, $value);
+        $crate::__tw_internal!(@push $classes, $value);
     }};
     (@append $classes:ident; $value:expr , $($rest:tt)*) => {{
         $crate::TwInput::append_to($value, &mut $classes);
@@ -158,7 +1339,9 @@ macro_rules! __tw_internal {
 
 #[cfg(test)]
 mod tests {
-    use super::{TailwindClasses, tw};
+    use super::{
+        ClassList, DisplayClasses, StrArray, TailwindClasses, TwRaw, When, tw, tw_static, when,
+    };
     use std::borrow::Cow;
 
     #[test]
@@ -180,6 +1363,91 @@ mod tests {
         assert_eq!(classes, expected);
     }
 
+    #[test]
+    fn tw_conditional_else() {
+        let dark = false;
+        let accent = String::from("ring-2 ring-blue-500");
+        let classes = tw!(
+            "base",
+            if dark => "bg-gray-900" else => "bg-white",
+            if !dark => accent.as_str() else => "ring-0",
+            if dark => Some("text-white") else => ["text-black"],
+            "p-4",
+        );
+        assert_eq!(
+            classes,
+            tw!("base bg-white ring-2 ring-blue-500 text-black p-4")
+        );
+        assert!(matches!(classes[1], Cow::Borrowed(_)));
+
+        let classes = tw!(if dark => "dark" else => "light");
+        assert_eq!(classes, tw!("light"));
+    }
+
+    #[test]
+    fn tw_match_arms() {
+        #[derive(Clone, Copy)]
+        enum Tone {
+            Info,
+            Warning,
+            Danger,
+        }
+        fn badge(tone: Tone) -> TailwindClasses {
+            tw!(
+                "rounded px-2",
+                match tone {
+                    Tone::Info => "bg-blue-100 text-blue-800",
+                    Tone::Warning => "bg-yellow-100 text-yellow-800",
+                    Tone::Danger => "bg-red-100 text-red-800",
+                },
+                "text-xs",
+            )
+        }
+        assert_eq!(
+            badge(Tone::Info),
+            tw!("rounded px-2 bg-blue-100 text-blue-800 text-xs")
+        );
+        assert_eq!(
+            badge(Tone::Danger),
+            tw!("rounded px-2 bg-red-100 text-red-800 text-xs")
+        );
+        assert!(matches!(
+            badge(Tone::Warning)[2],
+            Cow::Borrowed("bg-yellow-100")
+        ));
+
+        let level = 7_u8;
+        let classes = tw!(match level {
+            0 => "",
+            n if n > 5 => format!("z-{}", n * 10),
+            _ => Some("z-10"),
+        });
+        assert_eq!(classes, tw!("z-70"));
+        assert!(
+            tw!(match 0 {
+                0 => "",
+                _ => "hidden",
+            })
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn tw_capacity_hint() {
+        let active = true;
+        let classes = tw!(@cap(16), "p-4 flex", if active => "ring-2");
+        assert_eq!(classes, tw!("p-4 flex ring-2"));
+        assert!(classes.capacity() >= 16);
+
+        let empty: TailwindClasses = tw!(@cap(4));
+        assert!(empty.is_empty() && empty.capacity() >= 4);
+
+        let list = ClassList::from("p-4");
+        let classes = tw!(@cap(16), list, "flex");
+        assert_eq!(classes, tw!("p-4 flex"));
+        assert!(classes.capacity() >= 16);
+    }
+
     #[test]
     fn tw_expression_splits() {
         let input = String::from("p-4 text-sm");
@@ -203,6 +1471,342 @@ mod tests {
         assert_eq!(classes, expected);
     }
 
+    #[test]
+    fn tw_string_like_collections() {
+        let slice: &[&str] = &["p-4 flex", "gap-2"];
+        let boxed: [Box<str>; 1] = [Box::from("text-sm")];
+        let owned = vec![String::from("rounded")];
+        let classes = tw!(slice, StrArray(boxed), StrArray(&owned), owned.as_slice());
+        assert_eq!(classes, tw!("p-4 flex gap-2 text-sm rounded rounded"));
+    }
+
+    #[test]
+    fn tw_lazy_iterators_through_str_array() {
+        struct Field {
+            name: &'static str,
+            error: Option<u8>,
+        }
+        let fields = [
+            Field {
+                name: "email",
+                error: Some(2),
+            },
+            Field {
+                name: "name",
+                error: None,
+            },
+            Field {
+                name: "phone",
+                error: Some(1),
+            },
+        ];
+        let owned = fields
+            .iter()
+            .filter_map(|field| field.error.map(|width| format!("ring-{width}")));
+        let borrowed = fields
+            .iter()
+            .filter(|field| field.error.is_none())
+            .map(|field| Cow::Borrowed(field.name));
+        let classes = tw!("p-4", StrArray(owned), StrArray(borrowed));
+        assert_eq!(classes, tw!("p-4 ring-2 ring-1 name"));
+    }
+
+    #[test]
+    fn tw_appends_nested_containers_depth_first() {
+        let nested = vec![("a1 a2", ["b1", "b2 b3"]), ("c1", ["d1", "d2"])];
+        let classes = tw!(
+            "first",
+            (Some(["e1", "e2"]), nested, None::<&str>),
+            [vec!["f1"], vec!["f2 f3", "f4"]],
+            "last",
+        );
+        let tokens: Vec<&str> = classes.iter().map(AsRef::as_ref).collect();
+        assert_eq!(
+            tokens,
+            [
+                "first", "e1", "e2", "a1", "a2", "b1", "b2", "b3", "c1", "d1", "d2", "f1", "f2",
+                "f3", "f4", "last",
+            ]
+        );
+    }
+
+    #[test]
+    fn display_classes_joins_with_spaces() {
+        let mut classes = tw!("p-4 flex");
+        classes.insert(1, Cow::Borrowed(""));
+        assert_eq!(DisplayClasses(&classes).to_string(), "p-4 flex");
+        assert_eq!(DisplayClasses(&TailwindClasses::new()).to_string(), "");
+    }
+
+    #[test]
+    fn tw_raw_keeps_spaces() {
+        let classes = tw!("p-4", TwRaw("content-['a b']"), TwRaw(""), "flex");
+        let tokens: Vec<&str> = classes.iter().map(AsRef::as_ref).collect();
+        assert_eq!(tokens, ["p-4", "content-['a b']", "flex"]);
+    }
+
+    #[test]
+    fn tw_then_some_tokenizes() {
+        let active = true;
+        let disabled = false;
+        let classes = tw!(
+            active.then_some("bg-blue-600 text-white"),
+            disabled.then_some("opacity-50"),
+            active.then(|| String::from("shadow  ring")),
+        );
+        let tokens: Vec<&str> = classes.iter().map(AsRef::as_ref).collect();
+        assert_eq!(tokens, ["bg-blue-600", "text-white", "shadow", "ring"]);
+    }
+
+    #[test]
+    fn tw_accepts_method_calls_and_fields() {
+        trait Styled {
+            fn classes(&self) -> TailwindClasses;
+        }
+        struct Button {
+            base: TailwindClasses,
+            accent: Option<String>,
+            size: &'static str,
+        }
+        impl Styled for Button {
+            fn classes(&self) -> TailwindClasses {
+                tw!(&self.base, self.accent.clone())
+            }
+        }
+
+        let button = Button {
+            base: tw!("px-4 py-2"),
+            accent: Some("bg-rose-600 text-white".to_string()),
+            size: "text-sm",
+        };
+        let styled: &dyn Styled = &button;
+        let widgets: Vec<Box<dyn Styled>> = vec![Box::new(Button {
+            base: tw!("rounded"),
+            accent: None,
+            size: "text-lg",
+        })];
+        let large = false;
+
+        let classes = tw!(
+            styled.classes(),
+            button.size,
+            if !large => button.accent.as_deref().map(str::to_uppercase),
+            if large => button.size,
+            if widgets.len() == 1 => widgets[0].classes(),
+            extend &button.base,
+        );
+        let tokens: Vec<&str> = classes.iter().map(AsRef::as_ref).collect();
+        assert_eq!(
+            tokens,
+            [
+                "px-4",
+                "py-2",
+                "bg-rose-600",
+                "text-white",
+                "text-sm",
+                "BG-ROSE-600",
+                "TEXT-WHITE",
+                "rounded",
+                "px-4",
+                "py-2",
+            ]
+        );
+    }
+
+    #[test]
+    fn tw_accepts_short_lived_cow() {
+        let buffer = String::from("p-4 text-sm");
+        let borrowed: Cow<'_, str> = Cow::Borrowed(buffer.as_str());
+        let classes = tw!(borrowed, Cow::<str>::Owned("flex".to_string()));
+        let expected: TailwindClasses = vec![
+            Cow::Owned("p-4".to_string()),
+            Cow::Owned("text-sm".to_string()),
+            Cow::Owned("flex".to_string()),
+        ];
+        assert_eq!(classes, expected);
+    }
+
+    #[test]
+    fn tw_accepts_cow_with_a_non_static_lifetime() {
+        fn classes<'a>(input: &'a str) -> TailwindClasses {
+            let borrowed: Cow<'a, str> = Cow::Borrowed(input);
+            tw!(borrowed)
+        }
+        let buffer = format!("p-{} text-sm", 4);
+        assert_eq!(classes(&buffer), ["p-4", "text-sm"]);
+    }
+
+    #[test]
+    fn tw_splits_cow_values_alike_in_every_container() {
+        let expected = tw!("a b");
+        assert_eq!(tw(Cow::Borrowed("a b")), expected);
+        assert_eq!(tw(vec![Cow::Borrowed("a b")]), expected);
+        assert_eq!(tw([Cow::Borrowed("a b")]), expected);
+        assert_eq!(tw(Cow::<str>::Owned("a b".to_string())), expected);
+    }
+
+    #[test]
+    fn tw_keeps_class_lists_as_they_are_by_reference() {
+        let base = tw!("p-4 text-sm", TwRaw("content-['a b']"));
+        let classes = tw(&base);
+        assert_eq!(classes, base);
+        assert!(matches!(classes[0], Cow::Borrowed(_)));
+        assert_eq!(tw(base.clone()).len(), 4);
+        assert_eq!(tw!(extend & base, "flex").len(), 4);
+    }
+
+    #[test]
+    fn tw_accepts_string_references_and_boxed_str() {
+        let stored = String::from("  p-4\ttext-sm  flex ");
+        let boxed: Box<str> = stored.clone().into_boxed_str();
+        let expected = tw!(stored.clone());
+        assert_eq!(tw!(&stored), expected);
+        assert_eq!(tw!(boxed), expected);
+        assert_eq!(tw(Some(&stored)), expected);
+    }
+
+    #[test]
+    fn tw_accepts_mixed_tuples() {
+        let extra = String::from("text-sm font-bold");
+        let maybe: Option<&str> = Some("shadow");
+        let none: Option<&str> = None;
+        let classes = tw!(("p-4 flex", extra, maybe, none));
+        assert_eq!(classes, tw!("p-4 flex text-sm font-bold shadow"));
+
+        let twelve = tw(("a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l"));
+        assert_eq!(twelve, tw!("a b c d e f g h i j k l"));
+    }
+
+    #[test]
+    fn tw_moves_single_token_owned_strings() {
+        let token = String::from("ring-2");
+        let buffer = token.as_ptr();
+        let classes = tw!(Cow::<str>::Owned(token), String::from(" p-4 flex"));
+        assert_eq!(classes, tw!("ring-2 p-4 flex"));
+        assert_eq!(classes[0].as_ptr(), buffer);
+    }
+
+    #[test]
+    fn tw_extend_with_override() {
+        fn button() -> TailwindClasses {
+            tw!("px-4 py-2 rounded bg-blue-600 text-white hover:bg-blue-500")
+        }
+        let classes = tw!(extend button(), override "bg-red-600", "shadow");
+        let expected: TailwindClasses = vec![
+            Cow::Borrowed("px-4"),
+            Cow::Borrowed("py-2"),
+            Cow::Borrowed("rounded"),
+            Cow::Borrowed("text-white"),
+            Cow::Borrowed("hover:bg-blue-500"),
+            Cow::Borrowed("bg-red-600"),
+            Cow::Borrowed("shadow"),
+        ];
+        assert_eq!(classes, expected);
+    }
+
+    #[test]
+    fn tw_override_expression() {
+        let padding = String::from("px-2");
+        let classes = tw!("px-4 py-2", override padding);
+        let expected: TailwindClasses = vec![Cow::Borrowed("py-2"), Cow::Owned("px-2".to_string())];
+        assert_eq!(classes, expected);
+    }
+
+    #[test]
+    fn tw_remove_variant() {
+        let themed = tw!("bg-white dark:bg-gray-900 text-black dark:text-white");
+        let classes = tw!(extend &themed, remove_variant "dark", "p-4");
+        assert_eq!(classes, tw!("bg-white text-black p-4"));
+    }
+
+    #[test]
+    fn tw_static_borrows_tokens() {
+        let classes = tw_static("p-4  flex");
+        let tokens: TailwindClasses = classes.into_iter().collect();
+        assert_eq!(tokens, vec![Cow::Borrowed("p-4"), Cow::Borrowed("flex")]);
+        assert!(matches!(tw!(classes, "gap-2")[0], Cow::Borrowed("p-4")));
+    }
+
+    #[test]
+    fn tw_lit_borrows_named_constants() {
+        const BASE: &str = "p-4 flex";
+        static ACCENT: &str = "text-blue-600";
+
+        let classes = tw!(tw_lit!(BASE), if true => tw_lit!(ACCENT), BASE);
+        assert_eq!(classes, tw!("p-4 flex text-blue-600 p-4 flex"));
+        assert!(
+            classes[..3]
+                .iter()
+                .all(|token| matches!(token, Cow::Borrowed(_)))
+        );
+        assert!(matches!(classes[3], Cow::Owned(_)));
+    }
+
+    #[test]
+    fn when_appends_only_true_conditions() {
+        let extras = vec![
+            when(true, "ring-2"),
+            when(false, "opacity-50"),
+            When::new(true, "shadow"),
+        ];
+        let nested = When(true, (When(false, "hidden"), tw!("flex")));
+        assert_eq!(tw!("btn", extras, nested), tw!("btn ring-2 shadow flex"));
+    }
+
+    #[test]
+    fn debug_and_release_arms_follow_debug_assertions() {
+        let classes = tw!("p-4", debug "outline outline-red-500", release "shadow");
+        let expected = if cfg!(debug_assertions) {
+            tw!("p-4 outline outline-red-500")
+        } else {
+            tw!("p-4 shadow")
+        };
+        assert_eq!(classes, expected);
+
+        let debug = "flex";
+        assert_eq!(tw!(debug, "gap-2"), tw!("flex gap-2"));
+    }
+
+    #[test]
+    fn repeat_appends_one_result_per_index() {
+        let classes = tw!("flex", repeat 3 => |i| format!("delay-[{}ms]", i * 100), "gap-2");
+        assert_eq!(
+            classes,
+            tw!("flex delay-[0ms] delay-[100ms] delay-[200ms] gap-2")
+        );
+
+        let none = tw!(repeat 0 => |i| format!("z-{i}"));
+        assert!(none.is_empty());
+
+        let repeat = "p-4";
+        assert_eq!(tw!(repeat), tw!("p-4"));
+    }
+
+    #[test]
+    fn tw_csv_splits_commas() {
+        let pasted = String::from("flex,items-center");
+        let classes = tw_csv!("p-4,text-sm  bg-blue-500,", pasted);
+        let expected: TailwindClasses = vec![
+            Cow::Borrowed("p-4"),
+            Cow::Borrowed("text-sm"),
+            Cow::Borrowed("bg-blue-500"),
+            Cow::Owned("flex".to_string()),
+            Cow::Owned("items-center".to_string()),
+        ];
+        assert_eq!(classes, expected);
+    }
+
+    #[test]
+    fn tw_keeps_commas_in_arbitrary_values() {
+        let classes = tw!("grid grid-cols-[repeat(3,1fr)]");
+        let expected: TailwindClasses = vec![
+            Cow::Borrowed("grid"),
+            Cow::Borrowed("grid-cols-[repeat(3,1fr)]"),
+        ];
+        assert_eq!(classes, expected);
+        assert_eq!(tw_csv!("grid-cols-[repeat(3,1fr)]").len(), 2);
+    }
+
     #[test]
     fn tw_function_splits_str() {
         let classes = tw("p-4 text-sm");
@@ -212,4 +1816,130 @@ mod tests {
         ];
         assert_eq!(classes, expected);
     }
+
+    #[cfg(feature = "either")]
+    #[test]
+    fn either_appends_the_present_side() {
+        use either::Either;
+
+        let pick = |dense: bool| -> Either<&str, TailwindClasses> {
+            if dense {
+                Either::Left("p-1 text-xs")
+            } else {
+                Either::Right(tw!("p-4", "text-base"))
+            }
+        };
+        assert_eq!(tw(pick(true)), tw!("p-1 text-xs"));
+        assert_eq!(tw!("flex", pick(false)), tw!("flex p-4 text-base"));
+    }
+
+    #[test]
+    fn concat_and_adjacent_literals_stay_borrowed() {
+        let classes = tw!(concat!("gap-", "4"), "flex");
+        assert_eq!(classes, ["gap-4", "flex"]);
+        assert!(
+            classes
+                .iter()
+                .all(|class| matches!(class, Cow::Borrowed(_)))
+        );
+
+        let active = true;
+        let classes = tw!("px-4" "py-2", if active => "ring", concat!("p", "-1"));
+        assert_eq!(classes, ["px-4", "py-2", "ring", "p-1"]);
+        assert!(
+            classes
+                .iter()
+                .all(|class| matches!(class, Cow::Borrowed(_)))
+        );
+
+        assert_eq!(tw!("a" "b" "c"), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn class_list_is_a_drop_in_for_tailwind_classes() {
+        let mut list = ClassList::from("p-4 text-sm");
+        assert_eq!(list, tw!("p-4 text-sm"));
+        assert!(list.iter().all(|class| matches!(class, Cow::Owned(_))));
+
+        list.push(Cow::Borrowed("flex"));
+        assert_eq!(tw!("block", list.clone()), tw!("block p-4 text-sm flex"));
+        assert_eq!(DisplayClasses(&list).to_string(), "p-4 text-sm flex");
+        assert_eq!(TailwindClasses::from(list), tw!("p-4 text-sm flex"));
+        assert!(ClassList::from_tailwind("  ").is_empty());
+    }
+
+    #[test]
+    fn scale_formats_spacing_tokens() {
+        let n = 3;
+        let classes = tw!("flex", scale "gap" => 2.5, scale "p" => n, scale "m" => -4);
+        assert_eq!(classes, ["flex", "gap-2.5", "p-3", "-m-4"]);
+        assert!(super::on_spacing_scale(0.5));
+        assert!(super::on_spacing_scale(-96.0));
+        assert!(!super::on_spacing_scale(13.0));
+    }
+
+    #[test]
+    fn conditional_responsive_group() {
+        for wide in [true, false] {
+            let classes = tw!(
+                "grid",
+                if wide => responsive { base: "grid-cols-1", md: "grid-cols-3", xl2: "gap-8" },
+                "p-4",
+            );
+            if wide {
+                assert_eq!(
+                    classes,
+                    tw!("grid grid-cols-1 md:grid-cols-3 2xl:gap-8 p-4")
+                );
+            } else {
+                assert_eq!(classes, tw!("grid p-4"));
+            }
+        }
+    }
+
+    #[test]
+    fn count_matches_tw() {
+        const COUNT: usize = crate::tw_count!("p-4\tflex", "", " gap-2 \n");
+        assert_eq!(COUNT, tw!("p-4\tflex", "", " gap-2 \n").len());
+        assert_eq!(crate::tw_count!(), 0);
+    }
+
+    #[test]
+    fn class_lists_splat_by_reference_or_drain() {
+        let mut list = ClassList::from("p-4 flex");
+        assert_eq!(tw!("block", extend & list), tw!("block p-4 flex"));
+
+        let first = list[0].as_ptr();
+        let classes = tw!(drain list, "gap-2");
+        assert!(list.is_empty());
+        assert_eq!(classes, tw!("p-4 flex gap-2"));
+        assert_eq!(classes[0].as_ptr(), first);
+        assert_eq!(ClassList::from("m-1").into_classes(), tw!("m-1"));
+    }
+
+    #[test]
+    fn arrays_split_literals_at_compile_time() {
+        const EMPTY: [&str; 0] = crate::tw_arr!();
+        assert!(EMPTY.is_empty());
+        let tokens = crate::tw_arr!(" p-4\tflex ", "", "hover:bg-[url(a b)]");
+        assert_eq!(tokens, ["p-4", "flex", "hover:bg-[url(a", "b)]"]);
+        assert_eq!(
+            tokens.len(),
+            tw!(" p-4\tflex ", "", "hover:bg-[url(a b)]").len()
+        );
+    }
+
+    #[test]
+    fn pick_appends_the_indexed_element() {
+        let owned = String::from("bg-blue-500 text-white");
+        for (index, expected) in [
+            (0, tw!("p-4 bg-red-500")),
+            (2, tw!("p-4 bg-blue-500 text-white")),
+            (3, tw!("p-4")),
+        ] {
+            let classes = tw!("p-4", pick index => ["bg-red-500", ["bg-green-500"], owned.clone()]);
+            assert_eq!(classes, expected);
+        }
+        assert_eq!(tw!(pick 0 => [], "flex"), tw!("flex"));
+    }
 }
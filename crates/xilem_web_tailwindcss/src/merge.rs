@@ -0,0 +1,621 @@
+//! Conflict resolution between Tailwind utilities.
+//!
+//! Every known utility maps to a conflict group (for example `px-2` and
+//! `px-4` both belong to `px`). Two tokens conflict when they share a group
+//! and the same variant prefix, so `p-4` and `px-2` never conflict while
+//! `hover:bg-red-500` and `hover:bg-blue-500` do.
+//!
+//! The important modifier (`!p-4` or `p-4!`) does not change a token's group,
+//! but an important token is never overridden by a plain one.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::parse::{is_important, strip_important, variant_prefix};
+use crate::{TailwindClasses, TwInput, tw};
+
+/// Complete utilities that are not `stem-value` pairs.
+pub(crate) const KEYWORDS: &[(&str, &str)] = &[
+    ("block", "display"),
+    ("inline-block", "display"),
+    ("inline", "display"),
+    ("flex", "display"),
+    ("inline-flex", "display"),
+    ("grid", "display"),
+    ("inline-grid", "display"),
+    ("table", "display"),
+    ("inline-table", "display"),
+    ("table-row", "display"),
+    ("table-cell", "display"),
+    ("flow-root", "display"),
+    ("contents", "display"),
+    ("list-item", "display"),
+    ("hidden", "display"),
+    ("static", "position"),
+    ("fixed", "position"),
+    ("absolute", "position"),
+    ("relative", "position"),
+    ("sticky", "position"),
+    ("visible", "visibility"),
+    ("invisible", "visibility"),
+    ("collapse", "visibility"),
+    ("italic", "font-style"),
+    ("not-italic", "font-style"),
+    ("uppercase", "text-transform"),
+    ("lowercase", "text-transform"),
+    ("capitalize", "text-transform"),
+    ("normal-case", "text-transform"),
+    ("underline", "text-decoration-line"),
+    ("overline", "text-decoration-line"),
+    ("line-through", "text-decoration-line"),
+    ("no-underline", "text-decoration-line"),
+    ("truncate", "text-overflow"),
+];
+
+/// Stems whose values all fall into a single group named after the stem.
+pub(crate) const UNIFORM_STEMS: &[&str] = &[
+    "p",
+    "px",
+    "py",
+    "pt",
+    "pr",
+    "pb",
+    "pl",
+    "ps",
+    "pe",
+    "m",
+    "mx",
+    "my",
+    "mt",
+    "mr",
+    "mb",
+    "ml",
+    "ms",
+    "me",
+    "space-x",
+    "space-y",
+    "gap",
+    "gap-x",
+    "gap-y",
+    "w",
+    "h",
+    "size",
+    "min-w",
+    "min-h",
+    "max-w",
+    "max-h",
+    "inset",
+    "inset-x",
+    "inset-y",
+    "top",
+    "right",
+    "bottom",
+    "left",
+    "start",
+    "end",
+    "z",
+    "order",
+    "basis",
+    "grow",
+    "shrink",
+    "grid-cols",
+    "grid-rows",
+    "grid-flow",
+    "col",
+    "col-span",
+    "col-start",
+    "col-end",
+    "row",
+    "row-span",
+    "row-start",
+    "row-end",
+    "auto-cols",
+    "auto-rows",
+    "justify",
+    "justify-items",
+    "justify-self",
+    "items",
+    "self",
+    "place-content",
+    "place-items",
+    "place-self",
+    "rounded",
+    "rounded-t",
+    "rounded-r",
+    "rounded-b",
+    "rounded-l",
+    "rounded-s",
+    "rounded-e",
+    "rounded-tl",
+    "rounded-tr",
+    "rounded-br",
+    "rounded-bl",
+    "opacity",
+    "leading",
+    "tracking",
+    "whitespace",
+    "overflow",
+    "overflow-x",
+    "overflow-y",
+    "overscroll",
+    "cursor",
+    "aspect",
+    "columns",
+    "line-clamp",
+    "align",
+    "select",
+    "pointer-events",
+    "resize",
+    "transition",
+    "duration",
+    "ease",
+    "delay",
+    "animate",
+    "scale",
+    "scale-x",
+    "scale-y",
+    "rotate",
+    "translate-x",
+    "translate-y",
+    "skew-x",
+    "skew-y",
+    "origin",
+    "outline-offset",
+    "underline-offset",
+    "fill",
+    "accent",
+    "caret",
+    "blur",
+    "brightness",
+    "contrast",
+    "grayscale",
+    "invert",
+    "saturate",
+    "sepia",
+    "drop-shadow",
+    "backdrop-blur",
+    "mix-blend",
+];
+
+/// Border sides with their width and color groups.
+pub(crate) const BORDER_SIDES: &[(&str, &str, &str)] = &[
+    ("border", "border-width", "border-color"),
+    ("border-x", "border-x-width", "border-x-color"),
+    ("border-y", "border-y-width", "border-y-color"),
+    ("border-t", "border-t-width", "border-t-color"),
+    ("border-r", "border-r-width", "border-r-color"),
+    ("border-b", "border-b-width", "border-b-color"),
+    ("border-l", "border-l-width", "border-l-color"),
+    ("border-s", "border-s-width", "border-s-color"),
+    ("border-e", "border-e-width", "border-e-color"),
+];
+
+/// Stems that `stem_group` sorts into groups by their value.
+pub(crate) const CLASSIFIED_STEMS: &[&str] = &[
+    "text",
+    "bg",
+    "font",
+    "shadow",
+    "ring",
+    "ring-offset",
+    "outline",
+    "decoration",
+    "stroke",
+    "flex",
+    "content",
+    "object",
+    "list",
+];
+
+const TEXT_SIZES: &[&str] = &[
+    "xs", "sm", "base", "lg", "xl", "2xl", "3xl", "4xl", "5xl", "6xl", "7xl", "8xl", "9xl",
+];
+const TEXT_ALIGNS: &[&str] = &["left", "center", "right", "justify", "start", "end"];
+const FONT_WEIGHTS: &[&str] = &[
+    "thin",
+    "extralight",
+    "light",
+    "normal",
+    "medium",
+    "semibold",
+    "bold",
+    "extrabold",
+    "black",
+];
+const SHADOW_SIZES: &[&str] = &[
+    "", "2xs", "xs", "sm", "md", "lg", "xl", "2xl", "inner", "none",
+];
+const LINE_STYLES: &[&str] = &[
+    "solid", "dashed", "dotted", "double", "hidden", "none", "wavy",
+];
+const COLOR_KEYWORDS: &[&str] = &["inherit", "current", "transparent", "black", "white"];
+const PALETTES: &[&str] = &[
+    "slate", "gray", "zinc", "neutral", "stone", "red", "orange", "amber", "yellow", "lime",
+    "green", "emerald", "teal", "cyan", "sky", "blue", "indigo", "violet", "purple", "fuchsia",
+    "pink", "rose",
+];
+
+/// The conflict group of a token, ignoring its variants.
+pub(crate) fn conflict_group(token: &str) -> Option<&'static str> {
+    let base = &token[variant_prefix(token).len()..];
+    let (_, base) = strip_important(base);
+    let base = base.strip_prefix('-').unwrap_or(base);
+
+    if let Some(&(_, group)) = KEYWORDS.iter().find(|(keyword, _)| *keyword == base) {
+        return Some(group);
+    }
+
+    // Try the longest stem first: the whole token as a bare stem (`border`),
+    // then every dash outside an arbitrary value, from right to left.
+    let mut depth = 0_usize;
+    let mut dashes = Vec::new();
+    for (index, ch) in base.char_indices() {
+        match ch {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            '-' if depth == 0 => dashes.push(index),
+            _ => {}
+        }
+    }
+    std::iter::once(base.len())
+        .chain(dashes.into_iter().rev())
+        .find_map(|end| stem_group(&base[..end], base.get(end + 1..).unwrap_or("")))
+}
+
+/// Whether two tokens target the same CSS property under the same variants.
+pub(crate) fn conflicts(a: &str, b: &str) -> bool {
+    variant_prefix(a) == variant_prefix(b)
+        && conflict_group(a).is_some_and(|group| conflict_group(b) == Some(group))
+}
+
+/// Whether `base` is a complete utility that takes no value, like `flex`.
+pub(crate) fn is_keyword(base: &str) -> bool {
+    KEYWORDS.iter().any(|(keyword, _)| *keyword == base)
+}
+
+/// Whether `stem` is a known utility name that takes a value, like `bg`.
+pub(crate) fn is_stem(stem: &str) -> bool {
+    // Every stem maps to some group for a non-empty value.
+    stem_group(stem, "_").is_some()
+}
+
+fn stem_group(stem: &str, value: &str) -> Option<&'static str> {
+    if let Some(uniform) = UNIFORM_STEMS.iter().find(|s| **s == stem) {
+        return Some(uniform);
+    }
+    if let Some(&(_, width, color)) = BORDER_SIDES.iter().find(|(side, ..)| *side == stem) {
+        return Some(if stem == "border" && LINE_STYLES.contains(&value) {
+            "border-style"
+        } else if is_length(value) {
+            width
+        } else {
+            color
+        });
+    }
+
+    let value = value.split('/').next().unwrap_or(value);
+    let group = match stem {
+        "text" if TEXT_SIZES.contains(&value) || is_arbitrary_length(value) => "text-size",
+        "text" if TEXT_ALIGNS.contains(&value) => "text-align",
+        "text" if matches!(value, "wrap" | "nowrap" | "balance" | "pretty") => "text-wrap",
+        "text" if matches!(value, "ellipsis" | "clip") => "text-overflow",
+        "text" => "text-color",
+        "bg" if matches!(value, "auto" | "cover" | "contain") => "bg-size",
+        "bg" if matches!(value, "fixed" | "local" | "scroll") => "bg-attachment",
+        "bg" if value.starts_with("repeat") || value == "no-repeat" => "bg-repeat",
+        "bg" if is_position(value) => "bg-position",
+        "bg" if value == "none"
+            || value.starts_with("gradient")
+            || value.starts_with("linear")
+            || value.starts_with("radial")
+            || value.starts_with("conic")
+            || value.starts_with("[url(") =>
+        {
+            "bg-image"
+        }
+        "bg" => "bg-color",
+        "font" if FONT_WEIGHTS.contains(&value) || is_arbitrary_length(value) => "font-weight",
+        "font" => "font-family",
+        "shadow" if SHADOW_SIZES.contains(&value) || is_arbitrary_length(value) => "shadow",
+        "shadow" => "shadow-color",
+        "ring" if value == "inset" => "ring-inset",
+        "ring" if is_length(value) => "ring-width",
+        "ring" => "ring-color",
+        "ring-offset" if is_length(value) => "ring-offset-width",
+        "ring-offset" => "ring-offset-color",
+        "outline" if value.is_empty() || LINE_STYLES.contains(&value) => "outline-style",
+        "outline" if is_length(value) => "outline-width",
+        "outline" => "outline-color",
+        "decoration" if LINE_STYLES.contains(&value) => "decoration-style",
+        "decoration" if value == "auto" || value == "from-font" || is_length(value) => {
+            "decoration-thickness"
+        }
+        "decoration" => "decoration-color",
+        "stroke" if is_length(value) => "stroke-width",
+        "stroke" => "stroke",
+        "flex" if matches!(value, "row" | "row-reverse" | "col" | "col-reverse") => {
+            "flex-direction"
+        }
+        "flex" if matches!(value, "wrap" | "wrap-reverse" | "nowrap") => "flex-wrap",
+        "flex" if !value.is_empty() => "flex",
+        "content" if value.starts_with('[') || value.starts_with('(') || value == "none" => {
+            "content"
+        }
+        "content" => "align-content",
+        "object" if matches!(value, "contain" | "cover" | "fill" | "none" | "scale-down") => {
+            "object-fit"
+        }
+        "object" => "object-position",
+        "list" if matches!(value, "inside" | "outside") => "list-position",
+        "list" => "list-style",
+        _ => return None,
+    };
+    Some(group)
+}
+
+fn is_length(value: &str) -> bool {
+    value.is_empty()
+        || value.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+        || is_arbitrary_length(value)
+}
+
+fn is_arbitrary_length(value: &str) -> bool {
+    value.starts_with('[')
+        && !is_color(value)
+        && value[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.' || c == '-')
+        || value.starts_with("[length:")
+}
+
+fn is_position(value: &str) -> bool {
+    matches!(
+        value,
+        "bottom"
+            | "center"
+            | "left"
+            | "left-bottom"
+            | "left-top"
+            | "right"
+            | "right-bottom"
+            | "right-top"
+            | "top"
+    )
+}
+
+fn is_color(value: &str) -> bool {
+    if COLOR_KEYWORDS.contains(&value) {
+        return true;
+    }
+    if let Some(arbitrary) = value.strip_prefix('[') {
+        return arbitrary.starts_with('#')
+            || arbitrary.starts_with("rgb")
+            || arbitrary.starts_with("hsl")
+            || arbitrary.starts_with("oklch")
+            || arbitrary.starts_with("color:");
+    }
+    value.rsplit_once('-').is_some_and(|(palette, shade)| {
+        PALETTES.contains(&palette) && shade.bytes().all(|b| b.is_ascii_digit())
+    })
+}
+
+/// Append `overrides` to `classes`, first dropping every existing token that
+/// conflicts with one of the overrides.
+///
+/// ```
+/// use xilem_web_tailwindcss::{override_classes, tw};
+///
+/// let mut classes = tw!("px-4 py-2 bg-blue-600 text-white");
+/// override_classes(&mut classes, "bg-red-600");
+/// assert_eq!(classes, tw!("px-4 py-2 text-white bg-red-600"));
+/// ```
+pub fn override_classes(classes: &mut TailwindClasses, overrides: impl TwInput) {
+    apply_overrides(classes, tw(overrides));
+}
+
+/// Build a class list from `input`, keeping only the last token of every
+/// conflict group (per variant prefix) in its original position.
+///
+/// Tokens that do not conflict keep their order, and an earlier important
+/// token still wins over a later plain one, as with `override`.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, tw_merge};
+///
+/// let classes = tw_merge(("px-4 py-2 bg-blue-600", "px-2 p-4 bg-red-600"));
+/// assert_eq!(classes, tw!("py-2 px-2 p-4 bg-red-600"));
+/// ```
+pub fn tw_merge(input: impl TwInput) -> TailwindClasses {
+    let mut merged = tw(input);
+    // Walk back from the end so the first token seen for a variant prefix and
+    // group is the one to keep, unless an earlier important token beats it.
+    // Tokens without a group only drop exact repeats.
+    let mut keep = vec![true; merged.len()];
+    let mut kept: HashMap<(&str, Option<&str>), (usize, bool)> = HashMap::new();
+    for (index, token) in merged.iter().enumerate().rev() {
+        let important = is_important(token);
+        let key = match conflict_group(token) {
+            Some(group) => (variant_prefix(token), Some(group)),
+            None => (token.as_ref(), None),
+        };
+        match kept.get(&key) {
+            None => {
+                kept.insert(key, (index, important));
+            }
+            Some(&(later, false)) if important => {
+                keep[later] = false;
+                kept.insert(key, (index, important));
+            }
+            Some(_) => keep[index] = false,
+        }
+    }
+    let mut keep = keep.into_iter();
+    merged.retain(|_| keep.next().unwrap_or(true));
+    merged
+}
+
+/// Remove repeated tokens, keeping the first occurrence of each.
+///
+/// Tokens are compared by content, so a borrowed and an owned copy of the
+/// same class are duplicates. The remaining tokens keep their order.
+///
+/// ```
+/// use std::borrow::Cow;
+/// use xilem_web_tailwindcss::{dedup_classes, tw};
+///
+/// let mut classes = tw!("flex items-center", String::from("flex p-4"), "items-center");
+/// dedup_classes(&mut classes);
+/// assert_eq!(classes, tw!("flex items-center p-4"));
+/// assert!(matches!(classes[0], Cow::Borrowed(_)));
+/// ```
+pub fn dedup_classes(classes: &mut TailwindClasses) {
+    let mut seen = HashSet::with_capacity(classes.len());
+    let keep: Vec<bool> = classes
+        .iter()
+        .map(|token| seen.insert(token.as_ref()))
+        .collect();
+    let mut keep = keep.into_iter();
+    classes.retain(|_| keep.next().unwrap_or(true));
+}
+
+#[doc(hidden)]
+pub fn __tw_override_literal(classes: &mut TailwindClasses, overrides: &'static str) {
+    let mut tokens = Vec::new();
+    crate::__tw_push_literal(&mut tokens, overrides);
+    apply_overrides(classes, tokens);
+}
+
+fn apply_overrides(classes: &mut TailwindClasses, overrides: TailwindClasses) {
+    // A plain override loses to an important token it conflicts with.
+    let overrides: TailwindClasses = overrides
+        .into_iter()
+        .filter(|token| {
+            is_important(token)
+                || !classes
+                    .iter()
+                    .any(|existing| is_important(existing) && conflicts(token, existing))
+        })
+        .collect();
+    classes.retain(|existing| {
+        !overrides
+            .iter()
+            .any(|token| token == existing || conflicts(token, existing))
+    });
+    classes.extend(overrides);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{conflict_group, conflicts, dedup_classes, override_classes, tw_merge};
+    use crate::{TailwindClasses, tw};
+    use std::borrow::Cow;
+
+    #[test]
+    fn spacing_axes_are_distinct() {
+        assert!(conflicts("px-2", "px-4"));
+        assert!(!conflicts("p-4", "px-2"));
+        assert!(!conflicts("mt-2", "mb-2"));
+        assert!(conflicts("-mt-2", "mt-4"));
+    }
+
+    #[test]
+    fn ambiguous_stems_are_classified() {
+        assert_eq!(conflict_group("text-sm"), Some("text-size"));
+        assert_eq!(conflict_group("text-sm/6"), Some("text-size"));
+        assert_eq!(conflict_group("text-red-500"), Some("text-color"));
+        assert_eq!(conflict_group("text-center"), Some("text-align"));
+        assert_eq!(conflict_group("bg-[#ff0000]"), Some("bg-color"));
+        assert_eq!(conflict_group("bg-cover"), Some("bg-size"));
+        assert_eq!(conflict_group("border"), Some("border-width"));
+        assert_eq!(conflict_group("border-2"), Some("border-width"));
+        assert_eq!(conflict_group("border-dashed"), Some("border-style"));
+        assert_eq!(conflict_group("border-slate-200"), Some("border-color"));
+        assert_eq!(conflict_group("font-bold"), Some("font-weight"));
+        assert_eq!(conflict_group("font-mono"), Some("font-family"));
+        assert_eq!(conflict_group("w-[calc(100%-2rem)]"), Some("w"));
+        assert_eq!(conflict_group("flex-col"), Some("flex-direction"));
+    }
+
+    #[test]
+    fn display_keywords_conflict() {
+        assert!(conflicts("flex", "hidden"));
+        assert!(conflicts("block", "inline-flex"));
+        assert!(!conflicts("flex", "flex-col"));
+    }
+
+    #[test]
+    fn variants_scope_conflicts() {
+        assert!(conflicts("hover:bg-red-500", "hover:bg-blue-500"));
+        assert!(!conflicts("hover:bg-red-500", "bg-blue-500"));
+        assert!(!conflicts("md:p-4", "p-2"));
+    }
+
+    #[test]
+    fn unknown_utilities_never_conflict() {
+        assert_eq!(conflict_group("card-header"), None);
+        assert!(!conflicts("card", "card"));
+    }
+
+    #[test]
+    fn important_tokens_share_groups() {
+        assert!(conflicts("!p-4", "p-2"));
+        assert!(conflicts("p-4!", "!p-2"));
+        assert!(conflicts("hover:!-mt-2", "hover:mt-4!"));
+        assert_eq!(conflict_group("bg-red-500!"), Some("bg-color"));
+    }
+
+    #[test]
+    fn important_wins_over_plain_tokens() {
+        let mut classes = tw!("!p-4 flex");
+        override_classes(&mut classes, "p-2");
+        assert_eq!(classes, tw!("!p-4 flex"));
+
+        let mut classes = tw!("p-4! flex");
+        override_classes(&mut classes, "p-2");
+        assert_eq!(classes, tw!("p-4! flex"));
+
+        let mut classes = tw!("p-4 flex");
+        override_classes(&mut classes, "p-2!");
+        assert_eq!(classes, tw!("flex p-2!"));
+    }
+
+    #[test]
+    fn later_important_wins_over_earlier_important() {
+        let mut classes = tw!("!p-4 flex");
+        override_classes(&mut classes, "p-2!");
+        assert_eq!(classes, tw!("flex p-2!"));
+
+        let mut classes = tw!("hover:bg-red-500! bg-white");
+        override_classes(&mut classes, "hover:!bg-blue-500");
+        assert_eq!(classes, tw!("bg-white hover:!bg-blue-500"));
+    }
+
+    #[test]
+    fn merge_keeps_the_last_token_per_group() {
+        assert_eq!(
+            tw_merge("p-4 px-2 py-1 px-6 flex hidden"),
+            tw!("p-4 py-1 px-6 hidden")
+        );
+        assert_eq!(
+            tw_merge("text-red-500 text-sm text-blue-500 bg-white bg-black"),
+            tw!("text-sm text-blue-500 bg-black")
+        );
+        assert_eq!(
+            tw_merge("hover:bg-red-500 bg-white hover:bg-blue-500 card"),
+            tw!("bg-white hover:bg-blue-500 card")
+        );
+        assert_eq!(tw_merge("!m-2 m-4 mt-1"), tw!("!m-2 mt-1"));
+    }
+
+    #[test]
+    fn dedup_compares_borrowed_and_owned_tokens() {
+        let mut classes: TailwindClasses = vec![
+            Cow::Owned("flex".to_string()),
+            Cow::Borrowed("p-4"),
+            Cow::Borrowed("flex"),
+            Cow::Owned("p-4".to_string()),
+            Cow::Borrowed("gap-2"),
+        ];
+        dedup_classes(&mut classes);
+        assert_eq!(classes, tw!("flex p-4 gap-2"));
+        assert!(matches!(classes[0], Cow::Owned(_)));
+        assert!(matches!(classes[1], Cow::Borrowed(_)));
+    }
+}
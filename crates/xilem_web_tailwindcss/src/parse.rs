@@ -0,0 +1,190 @@
+//! Low-level parsing of individual class tokens.
+
+use std::borrow::Cow;
+
+use crate::TailwindClasses;
+
+/// Split a class token into its variant stack and base utility.
+///
+/// Colons inside `[...]` or `(...)` belong to arbitrary values and are not
+/// treated as variant separators, so `hover:bg-[url(a:b)]` yields
+/// `(["hover"], "bg-[url(a:b)]")`.
+#[must_use]
+pub fn split_variants(token: &str) -> (Vec<&str>, &str) {
+    let mut variants = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = 0;
+    for (index, ch) in token.char_indices() {
+        match ch {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            ':' if depth == 0 => {
+                variants.push(&token[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    (variants, &token[start..])
+}
+
+/// The variant prefix of a token including its trailing colon, e.g. `md:hover:`.
+pub(crate) fn variant_prefix(token: &str) -> &str {
+    let (_, base) = split_variants(token);
+    &token[..token.len() - base.len()]
+}
+
+/// Strip the important modifier from a base utility, accepting both the
+/// leading (`!p-4`, Tailwind v3) and trailing (`p-4!`, v4) forms.
+pub(crate) fn strip_important(base: &str) -> (bool, &str) {
+    if let Some(rest) = base.strip_prefix('!') {
+        (true, rest)
+    } else if let Some(rest) = base.strip_suffix('!') {
+        (true, rest)
+    } else {
+        (false, base)
+    }
+}
+
+/// Whether a token carries the important modifier.
+pub(crate) fn is_important(token: &str) -> bool {
+    strip_important(split_variants(token).1).0
+}
+
+/// Parse a raw HTML/JSX class attribute into a class list.
+///
+/// Accepts a bare value (`p-4 flex`), a quoted one (`"p-4 flex"`), or the whole
+/// attribute (`class="p-4 flex"`, `className={'p-4 flex'}`). Whitespace inside
+/// `[...]` or `(...)` does not split a token.
+///
+/// ```
+/// use xilem_web_tailwindcss::{parse_class_attr, tw};
+///
+/// assert_eq!(parse_class_attr(r#"class="p-4  flex""#), tw!("p-4 flex"));
+/// ```
+#[must_use]
+pub fn parse_class_attr(attr: &str) -> TailwindClasses {
+    split_tokens(attr_value(attr.trim()))
+        .map(|token| Cow::Owned(token.to_string()))
+        .collect()
+}
+
+fn attr_value(attr: &str) -> &str {
+    let value = match attr.split_once('=') {
+        Some((name, value)) if is_attr_name(name.trim()) => value.trim(),
+        _ => attr,
+    };
+    let value = value
+        .strip_prefix('{')
+        .and_then(|inner| inner.strip_suffix('}'))
+        .map_or(value, str::trim);
+    ['"', '\'', '`']
+        .into_iter()
+        .find_map(|quote| value.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(value)
+}
+
+fn is_attr_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+}
+
+/// Split on whitespace outside of brackets and parentheses.
+pub(crate) fn split_tokens(value: &str) -> impl Iterator<Item = &str> {
+    let mut tokens = Vec::new();
+    let mut depth = 0_usize;
+    let mut start = None;
+    for (index, ch) in value.char_indices() {
+        match ch {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            _ if ch.is_whitespace() && depth == 0 => {
+                if let Some(start) = start.take() {
+                    tokens.push(&value[start..index]);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        start.get_or_insert(index);
+    }
+    if let Some(start) = start {
+        tokens.push(&value[start..]);
+    }
+    tokens.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_important, parse_class_attr, split_variants, strip_important, variant_prefix};
+    use crate::tw;
+
+    #[test]
+    fn splits_nested_variants() {
+        assert_eq!(
+            split_variants("md:hover:bg-blue-500"),
+            (vec!["md", "hover"], "bg-blue-500")
+        );
+        assert_eq!(split_variants("p-4"), (vec![], "p-4"));
+        assert_eq!(variant_prefix("dark:md:p-4"), "dark:md:");
+    }
+
+    #[test]
+    fn ignores_colons_in_arbitrary_values() {
+        assert_eq!(
+            split_variants("hover:bg-[url(https://x.dev/a.png)]"),
+            (vec!["hover"], "bg-[url(https://x.dev/a.png)]")
+        );
+        assert_eq!(
+            split_variants("[&:nth-child(3)]:underline"),
+            (vec!["[&:nth-child(3)]"], "underline")
+        );
+    }
+
+    #[test]
+    fn strips_both_important_forms() {
+        assert_eq!(strip_important("!p-4"), (true, "p-4"));
+        assert_eq!(strip_important("p-4!"), (true, "p-4"));
+        assert_eq!(strip_important("p-4"), (false, "p-4"));
+        assert!(is_important("md:hover:!bg-red-500"));
+        assert!(is_important("md:-mt-2!"));
+        assert!(!is_important("[&:not(.a)]:p-4"));
+    }
+
+    #[test]
+    fn parses_quoted_attributes() {
+        let expected = tw!("px-4 py-2 text-sm");
+        assert_eq!(parse_class_attr("px-4 py-2 text-sm"), expected);
+        assert_eq!(parse_class_attr("'px-4 py-2 text-sm'"), expected);
+        assert_eq!(parse_class_attr(r#"class="px-4 py-2 text-sm""#), expected);
+        assert_eq!(
+            parse_class_attr(r#"className={"px-4 py-2 text-sm"}"#),
+            expected
+        );
+        assert_eq!(
+            parse_class_attr("className={`px-4 py-2 text-sm`}"),
+            expected
+        );
+    }
+
+    #[test]
+    fn parses_multi_line_attributes() {
+        let attr = "class=\"\n    flex items-center\n\tgap-2\n\"";
+        assert_eq!(parse_class_attr(attr), tw!("flex items-center gap-2"));
+        assert!(parse_class_attr(r#"class="""#).is_empty());
+    }
+
+    #[test]
+    fn keeps_arbitrary_values_intact() {
+        assert_eq!(
+            parse_class_attr(r#"class="grid-cols-[repeat(2, 1fr)] [&[data-open=true]]:block""#),
+            ["grid-cols-[repeat(2, 1fr)]", "[&[data-open=true]]:block"]
+        );
+        assert_eq!(
+            parse_class_attr("[&[data-state=open]]:flex p-4"),
+            tw!("[&[data-state=open]]:flex p-4")
+        );
+    }
+}
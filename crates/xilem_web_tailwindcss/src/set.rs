@@ -0,0 +1,149 @@
+//! An insertion-ordered class set that never holds a token twice.
+
+use indexmap::IndexSet;
+
+use crate::{CowStr, TailwindClasses, TwInput, tw};
+
+/// A class list that deduplicates as it is built.
+///
+/// Tokens keep the order in which they were first inserted, and removing one
+/// keeps the order of the rest, so a utility can be toggled on and off as
+/// state changes:
+///
+/// ```
+/// use xilem_web_tailwindcss::{ClassSet, tw};
+///
+/// let mut classes = ClassSet::new();
+/// classes.insert_all("flex items-center p-4");
+/// classes.insert_all(tw!("p-4 gap-2"));
+/// assert!(classes.remove("items-center"));
+/// assert_eq!(classes.into_classes(), tw!("flex p-4 gap-2"));
+/// ```
+///
+/// Two sets are equal when they hold the same tokens, in any order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClassSet(IndexSet<CowStr>);
+
+impl ClassSet {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a single token as is, without splitting it. Returns whether it
+    /// was new.
+    pub fn insert(&mut self, token: impl Into<CowStr>) -> bool {
+        self.0.insert(token.into())
+    }
+
+    /// Split `classes` like [`tw`](fn@tw) and insert every token that is not
+    /// present yet.
+    pub fn insert_all(&mut self, classes: impl TwInput) {
+        self.0.extend(tw(classes));
+    }
+
+    /// Remove `token`, keeping the order of the others. Returns whether it
+    /// was present.
+    pub fn remove(&mut self, token: &str) -> bool {
+        self.0.shift_remove(token)
+    }
+
+    #[must_use]
+    pub fn contains(&self, token: &str) -> bool {
+        self.0.contains(token)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CowStr> {
+        self.0.iter()
+    }
+
+    /// The tokens in insertion order.
+    #[must_use]
+    pub fn into_classes(self) -> TailwindClasses {
+        self.0.into_iter().collect()
+    }
+}
+
+impl From<&str> for ClassSet {
+    fn from(classes: &str) -> Self {
+        let mut set = Self::new();
+        set.insert_all(classes);
+        set
+    }
+}
+
+impl From<TailwindClasses> for ClassSet {
+    fn from(classes: TailwindClasses) -> Self {
+        Self(classes.into_iter().collect())
+    }
+}
+
+impl From<ClassSet> for TailwindClasses {
+    fn from(classes: ClassSet) -> Self {
+        classes.into_classes()
+    }
+}
+
+/// Appends the tokens in insertion order. The result is a plain list, so
+/// tokens already in it are not deduplicated against the set.
+impl TwInput for ClassSet {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        classes.extend(self.0);
+    }
+}
+
+impl TwInput for &ClassSet {
+    fn append_to(self, classes: &mut TailwindClasses) {
+        classes.extend(self.0.iter().cloned());
+    }
+}
+
+#[cfg(feature = "xilem_web")]
+impl xilem_web::modifiers::ClassIter for ClassSet {
+    fn class_iter(&self) -> impl Iterator<Item = CowStr> {
+        self.0.iter().cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ClassSet;
+    use crate::{TailwindClasses, tw};
+    use std::borrow::Cow;
+
+    #[test]
+    fn inserts_deduplicate_and_removes_keep_order() {
+        let mut set = ClassSet::from("p-4 flex");
+        assert!(!set.insert("flex"));
+        assert!(set.insert(String::from("gap-2")));
+        set.insert_all(("p-4", Some("shadow"), String::from("flex ring-2")));
+        assert_eq!(set.len(), 5);
+        assert!(set.contains("shadow"));
+
+        assert!(set.remove("flex"));
+        assert!(!set.remove("flex"));
+        assert!(!set.contains("flex"));
+        assert!(set.insert("flex"));
+
+        let classes = tw!("rounded", &set, "p-4");
+        assert_eq!(classes, tw!("rounded p-4 gap-2 shadow ring-2 flex p-4"));
+        assert_eq!(
+            TailwindClasses::from(set),
+            tw!("p-4 gap-2 shadow ring-2 flex")
+        );
+        assert_eq!(
+            ClassSet::from(tw!("a b a")).into_classes(),
+            vec![Cow::Borrowed("a"), Cow::Borrowed("b")]
+        );
+    }
+}
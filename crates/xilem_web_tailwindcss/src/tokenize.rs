@@ -0,0 +1,120 @@
+//! Pluggable splitting of class strings into tokens.
+
+use std::borrow::Cow;
+
+use crate::TailwindClasses;
+use crate::parse::split_tokens;
+
+/// How a class string is split into tokens.
+///
+/// `tw!` always uses [`Whitespace`]; `tw_with!` and [`tw_with`] take any
+/// tokenizer, e.g. for class syntaxes separated by `|`.
+///
+/// ```
+/// use xilem_web_tailwindcss::{TwTokenizer, tw, tw_with};
+///
+/// struct Pipes;
+///
+/// impl TwTokenizer for Pipes {
+///     fn tokenize<'a>(&self, input: &'a str) -> impl Iterator<Item = &'a str> {
+///         input.split('|').map(str::trim)
+///     }
+/// }
+///
+/// assert_eq!(tw_with!(Pipes, "p-4|flex", "gap-2"), tw!("p-4 flex gap-2"));
+/// ```
+pub trait TwTokenizer {
+    /// Split `input` into tokens. Empty tokens are dropped by the caller.
+    fn tokenize<'a>(&self, input: &'a str) -> impl Iterator<Item = &'a str>;
+}
+
+/// Splits on any whitespace. The tokenizer behind `tw!`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Whitespace;
+
+impl TwTokenizer for Whitespace {
+    fn tokenize<'a>(&self, input: &'a str) -> impl Iterator<Item = &'a str> {
+        input.split_whitespace()
+    }
+}
+
+/// Splits on whitespace outside `[...]` and `(...)`, so arbitrary values
+/// like `content-['a b']` stay one token.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BracketAware;
+
+impl TwTokenizer for BracketAware {
+    fn tokenize<'a>(&self, input: &'a str) -> impl Iterator<Item = &'a str> {
+        split_tokens(input)
+    }
+}
+
+/// Build a class list from a single string with a custom tokenizer.
+pub fn tw_with(tokenizer: &impl TwTokenizer, input: &str) -> TailwindClasses {
+    let mut classes = Vec::new();
+    __tw_push_str_with(tokenizer, &mut classes, input);
+    classes
+}
+
+#[doc(hidden)]
+pub fn __tw_push_literal_with(
+    tokenizer: &impl TwTokenizer,
+    classes: &mut TailwindClasses,
+    input: &'static str,
+) {
+    classes.extend(
+        tokenizer
+            .tokenize(input)
+            .filter(|token| !token.is_empty())
+            .map(Cow::Borrowed),
+    );
+}
+
+#[doc(hidden)]
+pub fn __tw_push_str_with(
+    tokenizer: &impl TwTokenizer,
+    classes: &mut TailwindClasses,
+    input: &str,
+) {
+    classes.extend(
+        tokenizer
+            .tokenize(input)
+            .filter(|token| !token.is_empty())
+            .map(|token| Cow::Owned(token.to_string())),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BracketAware, TwTokenizer, Whitespace, tw_with};
+    use crate::tw;
+    use std::borrow::Cow;
+
+    struct Pipes;
+
+    impl TwTokenizer for Pipes {
+        fn tokenize<'a>(&self, input: &'a str) -> impl Iterator<Item = &'a str> {
+            input.split('|')
+        }
+    }
+
+    #[test]
+    fn whitespace_matches_tw() {
+        assert_eq!(tw_with(&Whitespace, " p-4\tflex "), tw!("p-4 flex"));
+    }
+
+    #[test]
+    fn bracket_aware_keeps_arbitrary_values() {
+        let classes = tw_with(&BracketAware, "p-4 content-['a b'] flex");
+        assert_eq!(classes, ["p-4", "content-['a b']", "flex"]);
+    }
+
+    #[test]
+    fn custom_tokenizer_drops_empty_tokens() {
+        let name = String::from("gap-2||");
+        let classes = crate::tw_with!(Pipes, "p-4||flex", name);
+        assert_eq!(classes, tw!("p-4 flex gap-2"));
+        assert!(matches!(classes[0], Cow::Borrowed(_)));
+        assert!(matches!(classes[2], Cow::Owned(_)));
+    }
+}
@@ -0,0 +1,261 @@
+//! A typed view of a single class token.
+
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::CowStr;
+use crate::merge::{is_keyword, is_stem};
+use crate::parse::{split_variants, strip_important};
+
+/// A class token split into its parts.
+///
+/// `md:hover:-translate-x-[3px]` parses into the variants `["md", "hover"]`,
+/// `negative: true`, the prefix `translate-x` and the value `[3px]`.
+/// Displaying a `Utility` reassembles the original token, writing the
+/// important modifier in its trailing Tailwind v4 form (`p-4!`).
+///
+/// ```
+/// use xilem_web_tailwindcss::Utility;
+///
+/// let utility = Utility::parse("md:bg-red-500");
+/// assert_eq!(utility.variants, ["md"]);
+/// assert_eq!(utility.prefix, "bg");
+/// assert_eq!(utility.value.as_deref(), Some("red-500"));
+/// assert_eq!(utility.to_string(), "md:bg-red-500");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Utility {
+    /// The variant stack, outermost first, without colons.
+    pub variants: Vec<CowStr>,
+    /// Whether the token carries the important modifier, as in `!p-4` or
+    /// `p-4!`.
+    pub important: bool,
+    /// Whether the token starts with `-`, as in `-mt-4`.
+    pub negative: bool,
+    /// The utility name, e.g. `bg` or `translate-x`. Keywords such as `flex`
+    /// and arbitrary properties such as `[mask-type:alpha]` are all prefix.
+    pub prefix: CowStr,
+    /// Everything after the prefix, e.g. `red-500` or `[#bada55]`.
+    pub value: Option<CowStr>,
+}
+
+impl Utility {
+    /// Parse a single class token.
+    ///
+    /// The prefix is the longest known utility name the token starts with;
+    /// unknown utilities split at their first dash outside of brackets.
+    #[must_use]
+    pub fn parse(token: &str) -> Self {
+        let (variants, base) = split_variants(token);
+        let (important, base) = strip_important(base);
+        let (negative, base) = match base.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, base),
+        };
+        let (prefix, value) = split_prefix(base);
+        Self {
+            variants: variants
+                .into_iter()
+                .map(|variant| Cow::Owned(variant.to_string()))
+                .collect(),
+            important,
+            negative,
+            prefix: Cow::Owned(prefix.to_string()),
+            value: value.map(|value| Cow::Owned(value.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Utility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for variant in &self.variants {
+            write!(f, "{variant}:")?;
+        }
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str(&self.prefix)?;
+        if let Some(value) = &self.value {
+            write!(f, "-{value}")?;
+        }
+        if self.important {
+            f.write_str("!")?;
+        }
+        Ok(())
+    }
+}
+
+fn split_prefix(base: &str) -> (&str, Option<&str>) {
+    if base.starts_with('[') || is_keyword(base) || is_stem(base) {
+        return (base, None);
+    }
+    let dashes = dashes_outside_brackets(base);
+    dashes
+        .iter()
+        .rev()
+        .find(|&&index| is_stem(&base[..index]))
+        .or_else(|| dashes.first())
+        .map_or((base, None), |&index| {
+            (&base[..index], Some(&base[index + 1..]))
+        })
+}
+
+fn dashes_outside_brackets(base: &str) -> Vec<usize> {
+    let mut dashes = Vec::new();
+    let mut depth = 0_usize;
+    for (index, ch) in base.char_indices() {
+        match ch {
+            '[' | '(' => depth += 1,
+            ']' | ')' => depth = depth.saturating_sub(1),
+            '-' if depth == 0 && index > 0 => dashes.push(index),
+            _ => {}
+        }
+    }
+    dashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Utility;
+
+    fn parts(token: &str) -> (Vec<String>, bool, String, Option<String>) {
+        let utility = Utility::parse(token);
+        (
+            utility.variants.iter().map(ToString::to_string).collect(),
+            utility.negative,
+            utility.prefix.to_string(),
+            utility.value.map(|value| value.to_string()),
+        )
+    }
+
+    #[test]
+    fn splits_known_prefixes() {
+        assert_eq!(
+            parts("bg-red-500"),
+            (vec![], false, "bg".into(), Some("red-500".into()))
+        );
+        assert_eq!(
+            parts("ring-offset-2"),
+            (vec![], false, "ring-offset".into(), Some("2".into()))
+        );
+        assert_eq!(
+            parts("border-x-4"),
+            (vec![], false, "border-x".into(), Some("4".into()))
+        );
+        assert_eq!(
+            parts("backdrop-blur-sm"),
+            (vec![], false, "backdrop-blur".into(), Some("sm".into()))
+        );
+        assert_eq!(
+            parts("min-w-0"),
+            (vec![], false, "min-w".into(), Some("0".into()))
+        );
+    }
+
+    #[test]
+    fn keeps_keywords_and_bare_stems_whole() {
+        assert_eq!(parts("flex"), (vec![], false, "flex".into(), None));
+        assert_eq!(
+            parts("inline-flex"),
+            (vec![], false, "inline-flex".into(), None)
+        );
+        assert_eq!(parts("border"), (vec![], false, "border".into(), None));
+        assert_eq!(
+            parts("flex-row"),
+            (vec![], false, "flex".into(), Some("row".into()))
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_first_dash() {
+        assert_eq!(
+            parts("snap-x-mandatory"),
+            (vec![], false, "snap".into(), Some("x-mandatory".into()))
+        );
+        assert_eq!(parts("grow"), (vec![], false, "grow".into(), None));
+    }
+
+    #[test]
+    fn parses_arbitrary_values() {
+        assert_eq!(
+            parts("bg-[url(/a-b.png)]"),
+            (vec![], false, "bg".into(), Some("[url(/a-b.png)]".into()))
+        );
+        assert_eq!(
+            parts("text-[length:var(--size-lg)]"),
+            (
+                vec![],
+                false,
+                "text".into(),
+                Some("[length:var(--size-lg)]".into())
+            )
+        );
+        assert_eq!(
+            parts("[mask-type:luminance]"),
+            (vec![], false, "[mask-type:luminance]".into(), None)
+        );
+    }
+
+    #[test]
+    fn parses_negatives() {
+        assert_eq!(
+            parts("-mt-4"),
+            (vec![], true, "mt".into(), Some("4".into()))
+        );
+        assert_eq!(
+            parts("-m-[3px]"),
+            (vec![], true, "m".into(), Some("[3px]".into()))
+        );
+    }
+
+    #[test]
+    fn parses_nested_variants() {
+        assert_eq!(
+            parts("md:hover:-translate-x-2"),
+            (
+                vec!["md".into(), "hover".into()],
+                true,
+                "translate-x".into(),
+                Some("2".into())
+            )
+        );
+        assert_eq!(
+            parts("[&:nth-child(3)]:dark:p-4"),
+            (
+                vec!["[&:nth-child(3)]".into(), "dark".into()],
+                false,
+                "p".into(),
+                Some("4".into())
+            )
+        );
+    }
+
+    #[test]
+    fn parses_important_modifiers() {
+        for token in ["hover:!-mt-4", "hover:-mt-4!"] {
+            let utility = Utility::parse(token);
+            assert!(utility.important);
+            assert!(utility.negative);
+            assert_eq!(utility.prefix, "mt");
+            assert_eq!(utility.value.as_deref(), Some("4"));
+            assert_eq!(utility.to_string(), "hover:-mt-4!");
+        }
+        assert!(!Utility::parse("p-4").important);
+    }
+
+    #[test]
+    fn round_trips() {
+        for token in [
+            "p-4",
+            "flex",
+            "-mt-4",
+            "p-4!",
+            "md:hover:bg-[#bada55]",
+            "[mask-type:luminance]",
+            "group-hover:ring-offset-2",
+            "sm:-translate-y-[calc(100%-1rem)]",
+        ] {
+            assert_eq!(Utility::parse(token).to_string(), token);
+        }
+    }
+}
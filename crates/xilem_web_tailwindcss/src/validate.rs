@@ -0,0 +1,424 @@
+//! Policy checks for class lists contributed by untrusted code.
+
+use crate::merge::{BORDER_SIDES, CLASSIFIED_STEMS, KEYWORDS, UNIFORM_STEMS, conflict_group};
+use crate::parse::{split_variants, strip_important};
+use crate::{CowStr, TailwindClasses};
+
+/// Common utilities that take no value and have no conflict group.
+const STANDALONE: &[&str] = &[
+    "container",
+    "sr-only",
+    "not-sr-only",
+    "group",
+    "peer",
+    "antialiased",
+    "subpixel-antialiased",
+    "isolate",
+    "isolation-auto",
+    "border-collapse",
+    "border-separate",
+    "break-words",
+    "break-all",
+    "break-normal",
+    "box-border",
+    "box-content",
+    "appearance-none",
+    "touch-none",
+    "touch-auto",
+    "transform",
+    "transform-none",
+];
+
+/// Check every token's base utility against `allow`.
+///
+/// Variants are stripped before the check, so `hover:md:p-4` is tested as
+/// `p-4`. Returns the disallowed tokens, unchanged and in order.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, validate_against};
+///
+/// let spacing_only = |utility: &str| utility.starts_with("p-") || utility.starts_with("m-");
+/// assert!(validate_against(&tw!("p-4 md:m-2"), &spacing_only).is_ok());
+/// assert_eq!(
+///     validate_against(&tw!("p-4 hover:fixed"), &spacing_only).unwrap_err(),
+///     ["hover:fixed"]
+/// );
+/// ```
+///
+/// # Errors
+///
+/// Fails with the disallowed tokens if there are any.
+pub fn validate_against(
+    classes: &TailwindClasses,
+    allow: &dyn Fn(&str) -> bool,
+) -> Result<(), Vec<CowStr>> {
+    let disallowed: Vec<CowStr> = classes
+        .iter()
+        .filter(|token| !is_allowed(token, allow))
+        .cloned()
+        .collect();
+    if disallowed.is_empty() {
+        Ok(())
+    } else {
+        Err(disallowed)
+    }
+}
+
+/// The tokens of `classes` whose base utility passes `allow`, in order.
+#[must_use]
+pub fn filter_allowed(classes: &TailwindClasses, allow: &dyn Fn(&str) -> bool) -> TailwindClasses {
+    classes
+        .iter()
+        .filter(|token| is_allowed(token, allow))
+        .cloned()
+        .collect()
+}
+
+/// Whether `utility` (a token without variants) matches a built-in Tailwind
+/// utility pattern.
+///
+/// This is a heuristic over the same tables `override` uses, so it is meant
+/// for catching typos rather than as an exact list. Arbitrary properties such
+/// as `[mask-type:alpha]` are always accepted. Pass it to [`validate_against`]
+/// as a policy:
+///
+/// ```
+/// use xilem_web_tailwindcss::{is_known_utility, tw, validate_against};
+///
+/// assert_eq!(
+///     validate_against(&tw!("p-4 hover:bg-red-500 flx"), &is_known_utility).unwrap_err(),
+///     ["flx"]
+/// );
+/// ```
+#[must_use]
+pub fn is_known_utility(utility: &str) -> bool {
+    let (_, base) = strip_important(utility);
+    let base = base.strip_prefix('-').unwrap_or(base);
+    let named = base.split_once('/').map_or(base, |(name, _)| name);
+    (base.starts_with('[') && base.ends_with(']') && base.contains(':'))
+        || STANDALONE.contains(&named)
+        || conflict_group(base).is_some()
+}
+
+/// `tw!` keywords whose argument is not a class list: the variant of
+/// `remove_variant`, the prefix of `scale` and the index of `pick`. String
+/// literals there, and literal `match` patterns before `=>`, are not checked.
+#[doc(hidden)]
+pub const __TW_ARGUMENT_KEYWORDS: &[&str] = &["remove_variant", "scale", "pick"];
+
+/// With the `validate` feature, fails const evaluation (and so compilation)
+/// when a `tw!` literal contains a built-in stem without a valid value, such
+/// as `p-`. Other unknown names are taken as project classes, and built-in
+/// utilities behind a project prefix such as `tw-p-4` are accepted. Variants
+/// are not checked, since projects routinely add their own.
+#[doc(hidden)]
+pub const fn __tw_check_literal(value: &str) {
+    if !cfg!(feature = "validate") {
+        return;
+    }
+    let bytes = value.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+            index += 1;
+        }
+        let start = index;
+        while index < bytes.len() && !bytes[index].is_ascii_whitespace() {
+            index += 1;
+        }
+        if index > start {
+            let (_, rest) = bytes.split_at(start);
+            let (token, _) = rest.split_at(index - start);
+            if is_likely_typo(token) {
+                fail_literal(token);
+            }
+        }
+    }
+}
+
+const TYPO_MESSAGE: &[u8] = b"Tailwind utility without a valid value in `tw!` literal: ";
+
+/// Panic with [`TYPO_MESSAGE`] followed by `token`, which const panics can
+/// only print as a single `&str`. Long tokens are cut off.
+const fn fail_literal(token: &[u8]) -> ! {
+    let mut message = [0; 160];
+    let mut len = 0;
+    while len < TYPO_MESSAGE.len() {
+        message[len] = TYPO_MESSAGE[len];
+        len += 1;
+    }
+    let mut index = 0;
+    while index < token.len() && len < message.len() {
+        message[len] = token[index];
+        len += 1;
+        index += 1;
+    }
+    let (message, _) = message.split_at(len);
+    match core::str::from_utf8(message) {
+        Ok(message) => panic!("{}", message),
+        Err(_) => panic!("Tailwind utility without a valid value in `tw!` literal"),
+    }
+}
+
+/// Whether `token` is unknown but starts with a built-in stem whose value is
+/// missing (`p-`, `grid-cols-`).
+const fn is_likely_typo(token: &[u8]) -> bool {
+    let base = base_utility(token);
+    if is_known_base(base) || is_prefixed(base) {
+        return false;
+    }
+    let mut index = 0;
+    while index < UNIFORM_STEMS.len() {
+        if has_bad_value(base, UNIFORM_STEMS[index].as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    let mut index = 0;
+    while index < BORDER_SIDES.len() {
+        if has_bad_value(base, BORDER_SIDES[index].0.as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    let mut index = 0;
+    while index < CLASSIFIED_STEMS.len() {
+        if has_bad_value(base, CLASSIFIED_STEMS[index].as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+/// The base utility of a token: what follows the last `:` outside brackets,
+/// without the important marker and negative sign.
+const fn base_utility(token: &[u8]) -> &[u8] {
+    let mut depth = 0_usize;
+    let mut start = 0;
+    let mut index = 0;
+    while index < token.len() {
+        match token[index] {
+            b'[' | b'(' => depth += 1,
+            b']' | b')' => depth = depth.saturating_sub(1),
+            b':' if depth == 0 => start = index + 1,
+            _ => {}
+        }
+        index += 1;
+    }
+    let (_, mut base) = token.split_at(start);
+    if let [b'!', rest @ ..] = base {
+        base = rest;
+    } else if let [rest @ .., b'!'] = base {
+        base = rest;
+    }
+    if let [b'-', rest @ ..] = base {
+        base = rest;
+    }
+    base
+}
+
+/// A `const` version of [`is_known_utility`] for a base from [`base_utility`].
+const fn is_known_base(base: &[u8]) -> bool {
+    if let [b'[', .., b']'] = base {
+        return true;
+    }
+    let named = before_byte(base, b'/');
+
+    let mut index = 0;
+    while index < KEYWORDS.len() {
+        if bytes_eq(named, KEYWORDS[index].0.as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    let mut index = 0;
+    while index < STANDALONE.len() {
+        if bytes_eq(named, STANDALONE[index].as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    let mut index = 0;
+    while index < UNIFORM_STEMS.len() {
+        if has_stem(base, UNIFORM_STEMS[index].as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    let mut index = 0;
+    while index < BORDER_SIDES.len() {
+        if has_stem(base, BORDER_SIDES[index].0.as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    let mut index = 0;
+    while index < CLASSIFIED_STEMS.len() {
+        if has_stem(base, CLASSIFIED_STEMS[index].as_bytes()) {
+            return true;
+        }
+        index += 1;
+    }
+    false
+}
+
+/// Whether `base` is a built-in utility behind a project prefix, e.g.
+/// `tw-p-4` or `tw--mt-2`.
+const fn is_prefixed(base: &[u8]) -> bool {
+    let prefix = before_byte(base, b'-');
+    if prefix.is_empty() || prefix.len() == base.len() {
+        return false;
+    }
+    let (_, mut rest) = base.split_at(prefix.len() + 1);
+    if let [b'-', unsigned @ ..] = rest {
+        rest = unsigned;
+    }
+    !rest.is_empty() && is_known_base(rest)
+}
+
+/// Whether `base` is `stem-` followed by something [`has_stem`] rejects.
+const fn has_bad_value(base: &[u8], stem: &[u8]) -> bool {
+    if base.len() <= stem.len() {
+        return false;
+    }
+    let (head, rest) = base.split_at(stem.len());
+    bytes_eq(head, stem) && rest[0] == b'-'
+}
+
+/// `bytes` up to the first `byte`, or all of it.
+const fn before_byte(bytes: &[u8], byte: u8) -> &[u8] {
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == byte {
+            let (head, _) = bytes.split_at(index);
+            return head;
+        }
+        index += 1;
+    }
+    bytes
+}
+
+/// Whether `base` is `stem` alone or `stem-value`.
+const fn has_stem(base: &[u8], stem: &[u8]) -> bool {
+    if base.len() < stem.len() {
+        return false;
+    }
+    let (head, rest) = base.split_at(stem.len());
+    bytes_eq(head, stem) && (rest.is_empty() || (rest.len() > 1 && rest[0] == b'-'))
+}
+
+const fn bytes_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < a.len() {
+        if a[index] != b[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+fn is_allowed(token: &str, allow: &dyn Fn(&str) -> bool) -> bool {
+    let (_, base) = split_variants(token);
+    allow(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        base_utility, filter_allowed, is_known_base, is_known_utility, is_likely_typo,
+        validate_against,
+    };
+    use crate::tw;
+
+    fn colors_only(utility: &str) -> bool {
+        utility.starts_with("bg-") || utility.starts_with("text-")
+    }
+
+    #[test]
+    fn reports_disallowed_tokens_in_order() {
+        let classes = tw!("bg-red-500 fixed dark:text-white hover:inset-0");
+        assert_eq!(
+            validate_against(&classes, &colors_only).unwrap_err(),
+            ["fixed", "hover:inset-0"]
+        );
+        assert!(validate_against(&tw!("md:bg-[url(a:b)]"), &colors_only).is_ok());
+    }
+
+    #[test]
+    fn filter_keeps_allowed_tokens() {
+        let classes = tw!("bg-red-500 fixed dark:text-white");
+        assert_eq!(
+            filter_allowed(&classes, &colors_only),
+            tw!("bg-red-500 dark:text-white")
+        );
+    }
+
+    #[test]
+    fn recognizes_known_utilities() {
+        for utility in [
+            "p-4",
+            "-mt-2",
+            "!bg-red-500",
+            "text-[13px]",
+            "flex",
+            "border",
+            "sr-only",
+            "group/item",
+            "[mask-type:luminance]",
+        ] {
+            assert!(is_known_utility(utility), "{utility}");
+        }
+        for utility in ["flx", "textt-sm", "pading-4", "[13px]"] {
+            assert!(!is_known_utility(utility), "{utility}");
+        }
+    }
+
+    #[test]
+    fn const_check_matches_known_utilities() {
+        for token in [
+            "p-4",
+            "md:hover:-mt-2",
+            "!bg-red-500",
+            "bg-red-500!",
+            "w-[32px]",
+            "data-[state=open]:flex",
+            "border",
+            "sr-only",
+            "group/item",
+            "[mask-type:luminance]",
+        ] {
+            assert!(is_known_base(base_utility(token.as_bytes())), "{token}");
+        }
+        for token in ["tex-sm", "flx", "hover:pading-4", "p-"] {
+            assert!(!is_known_base(base_utility(token.as_bytes())), "{token}");
+        }
+    }
+
+    #[test]
+    fn flags_only_likely_typos() {
+        for token in ["p-", "hover:-mt-", "grid-cols-"] {
+            assert!(is_likely_typo(token.as_bytes()), "{token}");
+        }
+        for token in [
+            "p-4",
+            "btn",
+            "card",
+            "flx",
+            "badge",
+            "item",
+            "rows",
+            "fonts",
+            "tex-sm",
+            "tw-p-4",
+            "md:-tw-mt-2",
+            "!tw-flex",
+        ] {
+            assert!(!is_likely_typo(token.as_bytes()), "{token}");
+        }
+    }
+}
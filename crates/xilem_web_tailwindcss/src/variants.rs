@@ -0,0 +1,342 @@
+//! Helpers that operate on the variant stack of class tokens.
+//!
+//! The state helpers ([`hover`], [`focus`], [`dark`], ...) have names that
+//! easily collide with user code, so they are only reachable through this
+//! module, e.g. `variants::hover(...)`.
+
+use std::borrow::Cow;
+
+use crate::parse::{split_variants, variant_prefix};
+use crate::{CowStr, TailwindClasses, TwInput, tw};
+
+/// A responsive breakpoint, checked at compile time.
+///
+/// The named variants are Tailwind's defaults; use `Custom` for breakpoints
+/// defined in your theme, e.g. `Breakpoint::Custom("3xl")`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Breakpoint {
+    Sm,
+    Md,
+    Lg,
+    Xl,
+    Xl2,
+    Custom(&'static str),
+}
+
+impl Breakpoint {
+    /// The variant name without a colon, e.g. `"md"`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Sm => "sm",
+            Self::Md => "md",
+            Self::Lg => "lg",
+            Self::Xl => "xl",
+            Self::Xl2 => "2xl",
+            Self::Custom(name) => name,
+        }
+    }
+
+    /// The variant prefix including its colon, e.g. `"md:"`.
+    #[must_use]
+    pub fn prefix(self) -> CowStr {
+        match self {
+            Self::Sm => Cow::Borrowed("sm:"),
+            Self::Md => Cow::Borrowed("md:"),
+            Self::Lg => Cow::Borrowed("lg:"),
+            Self::Xl => Cow::Borrowed("xl:"),
+            Self::Xl2 => Cow::Borrowed("2xl:"),
+            Self::Custom(name) => Cow::Owned(format!("{name}:")),
+        }
+    }
+}
+
+impl From<Breakpoint> for &'static str {
+    fn from(breakpoint: Breakpoint) -> Self {
+        breakpoint.name()
+    }
+}
+
+/// Prefix every token of `classes` with the breakpoint variant.
+///
+/// ```
+/// use xilem_web_tailwindcss::{Breakpoint, responsive, tw};
+///
+/// assert_eq!(responsive(Breakpoint::Md, "p-4 flex"), tw!("md:p-4 md:flex"));
+/// ```
+pub fn responsive(breakpoint: Breakpoint, classes: impl TwInput) -> TailwindClasses {
+    with_variant(breakpoint.name(), classes)
+}
+
+/// Prefix every token of `classes` with an arbitrary variant.
+///
+/// A trailing colon is optional, so `"data-[open]"` and `"data-[open]:"` are
+/// equivalent. Also exported as `variants::variant`.
+///
+/// ```
+/// use xilem_web_tailwindcss::{tw, variants, with_variant};
+///
+/// assert_eq!(
+///     with_variant("aria-[expanded=true]", "bg-blue-600"),
+///     tw!("aria-[expanded=true]:bg-blue-600")
+/// );
+/// assert_eq!(
+///     variants::variant("group-hover", "underline"),
+///     tw!("group-hover:underline")
+/// );
+/// ```
+pub fn with_variant(variant: &str, classes: impl TwInput) -> TailwindClasses {
+    let variant = variant.strip_suffix(':').unwrap_or(variant);
+    tw(classes)
+        .into_iter()
+        .map(|token| Cow::Owned(format!("{variant}:{token}")))
+        .collect()
+}
+
+pub use with_variant as variant;
+
+/// Prefix every token of `classes` with `hover:`.
+///
+/// The variant helpers nest, applying the outer variant first:
+///
+/// ```
+/// use xilem_web_tailwindcss::tw;
+/// use xilem_web_tailwindcss::variants::{dark, hover};
+///
+/// assert_eq!(
+///     tw!("p-4", hover(["bg-blue-500", "text-white"])),
+///     tw!("p-4 hover:bg-blue-500 hover:text-white")
+/// );
+/// assert_eq!(dark(hover("bg-gray-700")), tw!("dark:hover:bg-gray-700"));
+/// ```
+pub fn hover(classes: impl TwInput) -> TailwindClasses {
+    with_variant("hover", classes)
+}
+
+/// Prefix every token of `classes` with `focus:`.
+pub fn focus(classes: impl TwInput) -> TailwindClasses {
+    with_variant("focus", classes)
+}
+
+/// Prefix every token of `classes` with `focus-visible:`.
+pub fn focus_visible(classes: impl TwInput) -> TailwindClasses {
+    with_variant("focus-visible", classes)
+}
+
+/// Prefix every token of `classes` with `active:`.
+pub fn active(classes: impl TwInput) -> TailwindClasses {
+    with_variant("active", classes)
+}
+
+/// Prefix every token of `classes` with `disabled:`.
+pub fn disabled(classes: impl TwInput) -> TailwindClasses {
+    with_variant("disabled", classes)
+}
+
+/// Prefix every token of `classes` with `dark:`.
+pub fn dark(classes: impl TwInput) -> TailwindClasses {
+    with_variant("dark", classes)
+}
+
+/// Remove every token whose variant stack contains `variant`.
+///
+/// A trailing colon is ignored, so `"dark"` and `"dark:"` are equivalent.
+///
+/// ```
+/// use xilem_web_tailwindcss::{remove_variant, tw};
+///
+/// let mut classes = tw!("bg-white dark:bg-black md:dark:text-white p-4");
+/// remove_variant(&mut classes, "dark");
+/// assert_eq!(classes, tw!("bg-white p-4"));
+/// ```
+pub fn remove_variant(classes: &mut TailwindClasses, variant: &str) {
+    let variant = variant.strip_suffix(':').unwrap_or(variant);
+    classes.retain(|token| !split_variants(token).0.contains(&variant));
+}
+
+/// Remove `prefix` from the utility of every token, e.g. to use classes
+/// written for a `prefix: 'tw-'` config in an unprefixed project.
+///
+/// Variants and the important and negative markers are kept, and tokens
+/// without the prefix pass through unchanged.
+///
+/// ```
+/// use xilem_web_tailwindcss::{strip_prefix, tw};
+///
+/// let vendor = "tw-p-4 hover:-tw-mt-2 !tw-flex shadow";
+/// assert_eq!(
+///     tw!("rounded", strip_prefix(vendor, "tw-")),
+///     tw!("rounded p-4 hover:-mt-2 !flex shadow")
+/// );
+/// ```
+pub fn strip_prefix(classes: impl TwInput, prefix: &str) -> TailwindClasses {
+    tw(classes)
+        .into_iter()
+        .map(|token| strip_token_prefix(&token, prefix).map_or(token, Cow::Owned))
+        .collect()
+}
+
+/// Insert `prefix` before the utility of every token, for classes used in a
+/// project whose tailwind v3 config sets `prefix: 'tw-'`; the inverse of
+/// [`strip_prefix`].
+///
+/// The prefix goes after the variants and the important and negative
+/// markers, and arbitrary properties such as `[mask-type:alpha]` are left
+/// alone. Tailwind v4 writes its prefix as a leading variant instead
+/// (`tw:hover:p-4`), which is `with_variant("tw", classes)`.
+///
+/// ```
+/// use xilem_web_tailwindcss::{prefix_all, tw};
+///
+/// assert_eq!(
+///     prefix_all("tw-", tw!("p-4 hover:bg-red-500 md:-mt-2 !flex")),
+///     tw("tw-p-4 hover:tw-bg-red-500 md:-tw-mt-2 !tw-flex")
+/// );
+/// ```
+#[must_use]
+pub fn prefix_all(prefix: &str, classes: TailwindClasses) -> TailwindClasses {
+    classes
+        .into_iter()
+        .map(|token| add_token_prefix(&token, prefix).map_or(token, Cow::Owned))
+        .collect()
+}
+
+/// Split a token into its variants (with the trailing `:`), important
+/// marker, negative sign and utility.
+fn split_markers(token: &str) -> (&str, &str, &str, &str) {
+    let variants = variant_prefix(token);
+    let base = &token[variants.len()..];
+    let important = if base.starts_with('!') { "!" } else { "" };
+    let base = &base[important.len()..];
+    let negative = if base.starts_with('-') { "-" } else { "" };
+    (variants, important, negative, &base[negative.len()..])
+}
+
+fn add_token_prefix(token: &str, prefix: &str) -> Option<String> {
+    let (variants, important, negative, utility) = split_markers(token);
+    if utility.is_empty() || utility.starts_with('[') {
+        return None;
+    }
+    Some(format!("{variants}{important}{negative}{prefix}{utility}"))
+}
+
+fn strip_token_prefix(token: &str, prefix: &str) -> Option<String> {
+    let (variants, important, negative, utility) = split_markers(token);
+    let utility = utility.strip_prefix(prefix)?;
+    Some(format!("{variants}{important}{negative}{utility}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        Breakpoint, dark, focus, hover, prefix_all, remove_variant, responsive, strip_prefix,
+        with_variant,
+    };
+    use crate::tw;
+
+    #[test]
+    fn breakpoint_prefixes() {
+        assert_eq!(Breakpoint::Xl2.prefix(), "2xl:");
+        assert_eq!(Breakpoint::Custom("3xl").prefix(), "3xl:");
+        assert_eq!(<&'static str>::from(Breakpoint::Sm), "sm");
+        assert_eq!(
+            responsive(Breakpoint::Lg, ["grid-cols-3", "hover:shadow"]),
+            tw!("lg:grid-cols-3 lg:hover:shadow")
+        );
+    }
+
+    #[test]
+    fn responsive_macros() {
+        let wide = true;
+        assert_eq!(
+            crate::tw_at!(Breakpoint::Md, "p-4", if wide => "w-full"),
+            tw!("md:p-4 md:w-full")
+        );
+        assert_eq!(
+            crate::tw_responsive!(
+                "p-2 text-sm",
+                Breakpoint::Md => "p-4",
+                Breakpoint::Custom("3xl") => "p-8 text-lg",
+            ),
+            tw!("p-2 text-sm md:p-4 3xl:p-8 3xl:text-lg")
+        );
+    }
+
+    #[test]
+    fn attribute_variant_macros() {
+        let open = false;
+        assert!(crate::tw_data!("open", open, "flex p-4").is_empty());
+        assert_eq!(
+            crate::tw_data!("open", !open, "flex", if true => "p-4"),
+            tw!("data-[open]:flex data-[open]:p-4")
+        );
+        assert_eq!(
+            crate::tw_data!("state=active" => "font-bold"),
+            tw!("data-[state=active]:font-bold")
+        );
+        assert_eq!(
+            crate::tw_aria!("expanded=true", true, "bg-blue-600"),
+            tw!("aria-[expanded=true]:bg-blue-600")
+        );
+        assert_eq!(
+            crate::tw_aria!("sort=ascending" => "rotate-180"),
+            with_variant("aria-[sort=ascending]:", "rotate-180")
+        );
+    }
+
+    #[test]
+    fn removes_only_matching_variant() {
+        let mut classes = tw!("dark:p-4 dark-mode hover:dark:underline group-hover:flex");
+        remove_variant(&mut classes, "dark:");
+        assert_eq!(classes, tw!("dark-mode group-hover:flex"));
+    }
+
+    #[test]
+    fn ignores_arbitrary_variant_contents() {
+        let mut classes = tw!("[&:hover]:underline hover:bg-red-500");
+        remove_variant(&mut classes, "hover");
+        assert_eq!(classes, tw!("[&:hover]:underline"));
+    }
+
+    #[test]
+    fn strips_prefix_behind_variants_and_markers() {
+        let classes = strip_prefix(
+            [
+                "md:hover:tw-bg-red-500",
+                "-tw-m-2",
+                "tw-p-4!",
+                "tw-[mask:none]",
+                "flex",
+            ],
+            "tw-",
+        );
+        assert_eq!(
+            classes,
+            tw!("md:hover:bg-red-500 -m-2 p-4! [mask:none] flex")
+        );
+    }
+
+    #[test]
+    fn variant_helpers_compose_in_order() {
+        assert_eq!(
+            responsive(Breakpoint::Md, dark(hover("bg-gray-700 text-white"))),
+            tw!("md:dark:hover:bg-gray-700 md:dark:hover:text-white")
+        );
+        assert_eq!(
+            tw!("ring-0", focus(["ring-2", "ring-blue-500"])),
+            tw!("ring-0 focus:ring-2 focus:ring-blue-500")
+        );
+        assert!(hover("").is_empty());
+    }
+
+    #[test]
+    fn prefix_all_round_trips_with_strip_prefix() {
+        let classes = tw!("p-4 group-hover:!bg-red-500 -inset-1 [mask-type:alpha] bg-red-500!");
+        let prefixed = prefix_all("tw-", classes.clone());
+        assert_eq!(
+            prefixed,
+            tw!("tw-p-4 group-hover:!tw-bg-red-500 -tw-inset-1 [mask-type:alpha] tw-bg-red-500!")
+        );
+        assert_eq!(strip_prefix(&prefixed, "tw-"), classes);
+    }
+}
@@ -0,0 +1,122 @@
+//! Pins the class compositions used by `examples/tailwind_basic`.
+//!
+//! The example itself targets wasm, so these helpers mirror its `tw!` calls
+//! and assert the exact tokens each dark-mode state produces.
+
+use xilem_web_tailwindcss::{TailwindClasses, tw};
+
+fn container_classes(dark: bool) -> TailwindClasses {
+    tw!(
+        "min-h-screen transition-colors",
+        if dark => "bg-slate-900 text-slate-100",
+        if !dark => "bg-slate-50 text-slate-900"
+    )
+}
+
+fn card_classes(dark: bool) -> TailwindClasses {
+    tw!(
+        "w-full max-w-md rounded-2xl border p-8 shadow-xl",
+        if dark => "border-slate-700 bg-slate-800",
+        if !dark => "border-slate-200 bg-white"
+    )
+}
+
+fn button_classes() -> TailwindClasses {
+    tw!(
+        "inline-flex items-center justify-center rounded-lg px-4 py-2",
+        "text-sm font-semibold transition-colors",
+        "bg-indigo-600 text-white",
+        "hover:bg-indigo-500 active:bg-indigo-700",
+        "focus:outline-none focus:ring-2 focus:ring-indigo-500 focus:ring-offset-2"
+    )
+}
+
+fn tokens(classes: &TailwindClasses) -> Vec<&str> {
+    classes.iter().map(AsRef::as_ref).collect()
+}
+
+#[test]
+fn container_light() {
+    assert_eq!(
+        tokens(&container_classes(false)),
+        [
+            "min-h-screen",
+            "transition-colors",
+            "bg-slate-50",
+            "text-slate-900"
+        ]
+    );
+}
+
+#[test]
+fn container_dark() {
+    assert_eq!(
+        tokens(&container_classes(true)),
+        [
+            "min-h-screen",
+            "transition-colors",
+            "bg-slate-900",
+            "text-slate-100"
+        ]
+    );
+}
+
+#[test]
+fn card_light() {
+    assert_eq!(
+        tokens(&card_classes(false)),
+        [
+            "w-full",
+            "max-w-md",
+            "rounded-2xl",
+            "border",
+            "p-8",
+            "shadow-xl",
+            "border-slate-200",
+            "bg-white",
+        ]
+    );
+}
+
+#[test]
+fn card_dark() {
+    assert_eq!(
+        tokens(&card_classes(true)),
+        [
+            "w-full",
+            "max-w-md",
+            "rounded-2xl",
+            "border",
+            "p-8",
+            "shadow-xl",
+            "border-slate-700",
+            "bg-slate-800",
+        ]
+    );
+}
+
+#[test]
+fn button_spans_multiple_literals() {
+    assert_eq!(
+        tokens(&button_classes()),
+        [
+            "inline-flex",
+            "items-center",
+            "justify-center",
+            "rounded-lg",
+            "px-4",
+            "py-2",
+            "text-sm",
+            "font-semibold",
+            "transition-colors",
+            "bg-indigo-600",
+            "text-white",
+            "hover:bg-indigo-500",
+            "active:bg-indigo-700",
+            "focus:outline-none",
+            "focus:ring-2",
+            "focus:ring-indigo-500",
+            "focus:ring-offset-2",
+        ]
+    );
+}
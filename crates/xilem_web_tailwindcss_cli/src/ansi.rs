@@ -0,0 +1,88 @@
+use clap::ValueEnum;
+use std::borrow::Cow;
+use std::env;
+use std::io::IsTerminal as _;
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// When to write colored output, including colors in tailwind and trunk
+/// output that passes through us.
+#[derive(ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color when stderr is a terminal, `NO_COLOR` is unset and `TERM` is not
+    /// `dumb`.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => {
+                std::io::stderr().is_terminal()
+                    && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                    && env::var_os("TERM").is_none_or(|term| term != "dumb")
+            }
+        }
+    }
+}
+
+/// Remove ANSI escape sequences (colors, cursor movement, hyperlinks) from a
+/// line of child process output.
+pub fn strip(line: &str) -> Cow<'_, str> {
+    if !line.contains(ESC) {
+        return Cow::Borrowed(line);
+    }
+    let mut stripped = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch != ESC {
+            stripped.push(ch);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates up to a final byte in `@`..=`~`.
+            Some('[') => {
+                for ch in chars.by_ref() {
+                    if ('@'..='~').contains(&ch) {
+                        break;
+                    }
+                }
+            }
+            // OSC: terminated by BEL or `ESC \`.
+            Some(']') => {
+                while let Some(ch) = chars.next() {
+                    if ch == BEL {
+                        break;
+                    }
+                    if ch == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Any other escape is two characters long.
+            _ => {}
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip;
+
+    #[test]
+    fn strips_escape_sequences() {
+        assert_eq!(strip("plain"), "plain");
+        assert_eq!(strip("\x1b[1;31merror\x1b[0m: bad"), "error: bad");
+        assert_eq!(strip("\x1b[2K\x1b[1Gdone"), "done");
+        assert_eq!(strip("\x1b]8;;https://x.dev\x07link\x1b]8;;\x1b\\"), "link");
+        assert_eq!(strip("a\x1b7b\x1b8c"), "abc");
+    }
+}
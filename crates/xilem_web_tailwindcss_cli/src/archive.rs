@@ -0,0 +1,153 @@
+use anyhow::{Context, Result, anyhow};
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+/// How a downloaded release asset is packaged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetKind {
+    Binary,
+    TarGz,
+    Zip,
+}
+
+impl AssetKind {
+    /// Detect the packaging from the asset URL, falling back to the leading
+    /// magic bytes for mirrors that archive assets under a bare name.
+    pub fn detect(url: &str, bytes: &[u8]) -> Self {
+        let path = url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or(url)
+            .to_ascii_lowercase();
+        let has_suffix = |suffix: &str| path.ends_with(suffix);
+        if has_suffix(".tar.gz") || has_suffix(".tgz") || bytes.starts_with(&[0x1f, 0x8b]) {
+            Self::TarGz
+        } else if has_suffix(".zip") || bytes.starts_with(b"PK\x03\x04") {
+            Self::Zip
+        } else {
+            Self::Binary
+        }
+    }
+}
+
+/// The file name at the end of an asset URL, without a query or fragment.
+pub fn asset_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Return the tailwindcss executable contained in a downloaded asset.
+pub fn extract_binary(kind: AssetKind, bytes: Vec<u8>) -> Result<Vec<u8>> {
+    match kind {
+        AssetKind::Binary => Ok(bytes),
+        AssetKind::TarGz => extract_tar_gz(&bytes),
+        AssetKind::Zip => extract_zip(bytes),
+    }
+}
+
+fn extract_tar_gz(bytes: &[u8]) -> Result<Vec<u8>> {
+    let mut archive = tar::Archive::new(GzDecoder::new(bytes));
+    for entry in archive
+        .entries()
+        .context("failed to read tailwindcss archive")?
+    {
+        let mut entry = entry.context("failed to read tailwindcss archive entry")?;
+        if !entry.header().entry_type().is_file() || !is_tailwind_binary(&entry.path()?) {
+            continue;
+        }
+        let mut binary = Vec::new();
+        entry
+            .read_to_end(&mut binary)
+            .context("failed to extract tailwindcss from archive")?;
+        return Ok(binary);
+    }
+    Err(anyhow!("no tailwindcss executable found in .tar.gz asset"))
+}
+
+fn extract_zip(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(bytes)).context("failed to read tailwindcss archive")?;
+    for index in 0..archive.len() {
+        let mut file = archive
+            .by_index(index)
+            .context("failed to read tailwindcss archive entry")?;
+        let is_binary = file.is_file()
+            && file
+                .enclosed_name()
+                .is_some_and(|path| is_tailwind_binary(&path));
+        if !is_binary {
+            continue;
+        }
+        let mut binary = Vec::new();
+        file.read_to_end(&mut binary)
+            .context("failed to extract tailwindcss from archive")?;
+        return Ok(binary);
+    }
+    Err(anyhow!("no tailwindcss executable found in .zip asset"))
+}
+
+/// Matches `tailwindcss`, `tailwindcss.exe` and platform-suffixed names like
+/// `tailwindcss-linux-x64`.
+fn is_tailwind_binary(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with("tailwindcss"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AssetKind, asset_name, extract_binary};
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::{Cursor, Write};
+
+    const BINARY: &[u8] = b"\x7fELF fake tailwind";
+
+    #[test]
+    fn bare_binary_passes_through() {
+        let kind = AssetKind::detect("https://x.dev/tailwindcss-linux-x64", BINARY);
+        assert_eq!(kind, AssetKind::Binary);
+        assert_eq!(extract_binary(kind, BINARY.to_vec()).unwrap(), BINARY);
+    }
+
+    #[test]
+    fn asset_names_drop_the_query() {
+        assert_eq!(
+            asset_name("https://x.dev/v4.1.5/tailwindcss-linux-x64.tar.gz?sig=a/b"),
+            "tailwindcss-linux-x64.tar.gz"
+        );
+        assert_eq!(asset_name("tailwindcss-linux-x64"), "tailwindcss-linux-x64");
+    }
+
+    #[test]
+    fn extracts_from_tar_gz() {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+        for (name, data) in [("pkg/README.md", &b"docs"[..]), ("pkg/tailwindcss", BINARY)] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, name, data).unwrap();
+        }
+        let bytes = builder.into_inner().unwrap().finish().unwrap();
+
+        let kind = AssetKind::detect("https://x.dev/tailwindcss.tar.gz?x=1", &bytes);
+        assert_eq!(kind, AssetKind::TarGz);
+        assert_eq!(extract_binary(kind, bytes).unwrap(), BINARY);
+    }
+
+    #[test]
+    fn extracts_from_zip() {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("LICENSE", options).unwrap();
+        writer.write_all(b"MIT").unwrap();
+        writer.start_file("tailwindcss.exe", options).unwrap();
+        writer.write_all(BINARY).unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let kind = AssetKind::detect("https://mirror.dev/tailwindcss-windows-x64.exe", &bytes);
+        assert_eq!(kind, AssetKind::Zip);
+        assert_eq!(extract_binary(kind, bytes).unwrap(), BINARY);
+    }
+}
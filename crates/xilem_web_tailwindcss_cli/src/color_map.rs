@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Tailwind v4 emits a `--color-<name>` theme variable for every color used.
+const THEME_PREFIX: &str = "--color-";
+
+/// Utilities whose single declaration is a color in tailwind v3 output, with
+/// the property that holds it.
+const V3_COLOR_UTILITIES: &[(&str, &str)] = &[
+    ("bg", "background-color"),
+    ("text", "color"),
+    ("border", "border-color"),
+    ("outline", "outline-color"),
+    ("ring", "--tw-ring-color"),
+    ("decoration", "text-decoration-color"),
+    ("accent", "accent-color"),
+    ("caret", "caret-color"),
+    ("fill", "fill"),
+    ("stroke", "stroke"),
+];
+
+/// Write a JSON object mapping every color the built CSS uses (e.g.
+/// `red-500`) to its resolved value.
+pub fn write_color_map(css_path: &Path, map_path: &Path) -> Result<()> {
+    let css = fs::read_to_string(css_path)
+        .with_context(|| format!("failed to read {}", css_path.display()))?;
+    let colors = extract(&css);
+    if let Some(parent) = map_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    let json = serde_json::to_string_pretty(&colors).context("failed to encode the color map")?;
+    fs::write(map_path, json + "\n")
+        .with_context(|| format!("failed to write {}", map_path.display()))?;
+    info!("Wrote {} colors to {}", colors.len(), map_path.display());
+    Ok(())
+}
+
+/// Colors from v4 theme variables, or from v3 color utilities when the CSS
+/// has no theme variables.
+fn extract(css: &str) -> BTreeMap<String, String> {
+    let mut theme = BTreeMap::new();
+    let mut utilities = BTreeMap::new();
+    for (selector, block) in rules(css) {
+        for (property, value) in declarations(block) {
+            if let Some(name) = property.strip_prefix(THEME_PREFIX) {
+                theme.insert(name.to_string(), value.to_string());
+            } else if let Some(name) = v3_color(selector, property) {
+                utilities
+                    .entry(name.to_string())
+                    .or_insert_with(|| value.to_string());
+            }
+        }
+    }
+    if theme.is_empty() { utilities } else { theme }
+}
+
+/// The color name of a plain v3 utility selector such as `.bg-red-500`.
+fn v3_color<'a>(selector: &'a str, property: &str) -> Option<&'a str> {
+    let class = selector.trim().strip_prefix('.')?;
+    if class.contains(['\\', ':', ' ', ',', '.']) {
+        return None;
+    }
+    V3_COLOR_UTILITIES
+        .iter()
+        .find_map(|(prefix, color_property)| {
+            let name = class.strip_prefix(prefix)?.strip_prefix('-')?;
+            (property == *color_property).then_some(name)
+        })
+}
+
+/// Every innermost `selector { declarations }` block.
+fn rules(css: &str) -> Vec<(&str, &str)> {
+    let mut rules = Vec::new();
+    let mut start = 0;
+    let mut open = None;
+    for (index, ch) in css.char_indices() {
+        match ch {
+            '{' => {
+                open = Some((start, index));
+                start = index + 1;
+            }
+            '}' => {
+                if let Some((selector_start, brace)) = open.take() {
+                    rules.push((&css[selector_start..brace], &css[brace + 1..index]));
+                }
+                start = index + 1;
+            }
+            ';' if open.is_none() => start = index + 1,
+            _ => {}
+        }
+    }
+    rules
+}
+
+fn declarations(block: &str) -> impl Iterator<Item = (&str, &str)> {
+    block.split(';').filter_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+        Some((property.trim(), value.trim()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::extract;
+
+    #[test]
+    fn reads_v4_theme_variables() {
+        let css = "@layer theme{:root,:host{--color-red-500:oklch(63.7% .237 25.331);--color-white:#fff;--spacing:.25rem}}@layer utilities{.bg-red-500{background-color:var(--color-red-500)}}";
+        let colors = extract(css);
+        assert_eq!(colors.len(), 2);
+        assert_eq!(colors["red-500"], "oklch(63.7% .237 25.331)");
+        assert_eq!(colors["white"], "#fff");
+    }
+
+    #[test]
+    fn reads_v3_color_utilities() {
+        let css = ".bg-red-500 {\n  --tw-bg-opacity: 1;\n  background-color: rgb(239 68 68 / var(--tw-bg-opacity, 1));\n}\n.text-white { color: #fff; }\n.hover\\:bg-blue-500:hover { background-color: blue; }\n.p-4 { padding: 1rem; }\n";
+        let colors = extract(css);
+        assert_eq!(colors.len(), 2);
+        assert_eq!(
+            colors["red-500"],
+            "rgb(239 68 68 / var(--tw-bg-opacity, 1))"
+        );
+        assert_eq!(colors["white"], "#fff");
+    }
+}
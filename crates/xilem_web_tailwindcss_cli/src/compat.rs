@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::install;
+use crate::sources;
+
+/// The tailwind major version the `init` templates are written for.
+pub const TEMPLATE_MAJOR: u32 = 4;
+
+/// The major version in `tailwindcss --help` output, e.g. `tailwindcss v4.1.5`.
+pub fn parse_help_major(help: &str) -> Option<u32> {
+    help.split_whitespace()
+        .filter_map(|word| word.strip_prefix('v'))
+        .find_map(|version| version.split('.').next()?.parse().ok())
+}
+
+/// The major version an input CSS file is written for: `@tailwind`
+/// directives are v3, `@import "tailwindcss"` is v4.
+pub fn input_major(css: &str) -> Option<u32> {
+    css.lines().map(str::trim).find_map(entry_major)
+}
+
+/// The major version a tailwind entry line belongs to, if it is one.
+fn entry_major(line: &str) -> Option<u32> {
+    if line.starts_with("@tailwind ") {
+        Some(3)
+    } else if line.starts_with("@import \"tailwindcss") || line.starts_with("@import 'tailwindcss")
+    {
+        Some(4)
+    } else {
+        None
+    }
+}
+
+/// The 1-based numbers of tailwind entry lines that exactly repeat an
+/// earlier line of the same stylesheet, e.g. a second `@import "tailwindcss";`.
+pub fn duplicate_entry_lines(css: &str) -> Vec<usize> {
+    let mut seen = HashSet::new();
+    css.lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| entry_major(line).is_some() && !seen.insert(*line))
+        .map(|(index, _)| index + 1)
+        .collect()
+}
+
+/// Warn about repeated tailwind entry lines in the input and the stylesheets
+/// it imports, or with `fix` remove them after backing up the original.
+///
+/// Only exact repeats are touched; differing imports (e.g. with a `layer()`
+/// or `prefix()`) are left for the user to reconcile.
+pub fn check_duplicate_entries(input_path: &Path, fix: bool) -> Result<()> {
+    for (path, css) in sources::stylesheets(input_path) {
+        let duplicates = duplicate_entry_lines(&css);
+        if duplicates.is_empty() {
+            continue;
+        }
+        let lines = duplicates
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !fix {
+            warn!(
+                "{} repeats a tailwind import on line {lines}, which can generate the CSS twice; pass --fix-input to remove the duplicates",
+                path.display()
+            );
+            continue;
+        }
+        let backup = install::with_suffix(&path, "bak");
+        fs::write(&backup, &css)
+            .with_context(|| format!("failed to write {}", backup.display()))?;
+        let fixed: String = css
+            .split_inclusive('\n')
+            .enumerate()
+            .filter(|(index, _)| !duplicates.contains(&(index + 1)))
+            .map(|(_, line)| line)
+            .collect();
+        fs::write(&path, fixed).with_context(|| format!("failed to write {}", path.display()))?;
+        info!(
+            "Removed the duplicate tailwind import on line {lines} of {}; the original is in {}",
+            path.display(),
+            backup.display()
+        );
+    }
+    Ok(())
+}
+
+/// Warn when the installed tailwind does not match the templates or the
+/// input's directives, which otherwise shows up as silently empty CSS.
+pub fn warn_on_mismatch(binary_major: u32, input_path: &Path) {
+    if binary_major != TEMPLATE_MAJOR {
+        warn!(
+            "tailwindcss v{binary_major} is installed, but the `init` templates target v{TEMPLATE_MAJOR}; the generated input and config may be incompatible"
+        );
+    }
+    let input_major = sources::stylesheets(input_path)
+        .iter()
+        .find_map(|(_, css)| input_major(css));
+    if let Some(input_major) = input_major.filter(|major| *major != binary_major) {
+        warn!(
+            "{} uses tailwind v{input_major} directives, but tailwindcss v{binary_major} is installed; the output CSS may be empty",
+            input_path.display()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{duplicate_entry_lines, input_major, parse_help_major};
+
+    #[test]
+    fn detects_major_versions() {
+        assert_eq!(parse_help_major("≈ tailwindcss v4.1.5\n\nUsage:"), Some(4));
+        assert_eq!(parse_help_major("tailwindcss v3.4.17"), Some(3));
+        assert_eq!(
+            parse_help_major("Usage: tailwindcss [--input input.css]"),
+            None
+        );
+
+        assert_eq!(
+            input_major("/* app */\n@import \"tailwindcss\";\n"),
+            Some(4)
+        );
+        assert_eq!(
+            input_major("@tailwind base;\n@tailwind utilities;\n"),
+            Some(3)
+        );
+        assert_eq!(input_major(".btn { color: red; }"), None);
+    }
+
+    #[test]
+    fn finds_only_exact_duplicate_entries() {
+        let css = "@import \"tailwindcss\";\n@source \"../src\";\n  @import \"tailwindcss\";\n@import \"tailwindcss\" prefix(tw);\n@import \"tailwindcss\";\n";
+        assert_eq!(duplicate_entry_lines(css), [3, 5]);
+        assert_eq!(
+            duplicate_entry_lines("@tailwind base;\n@tailwind utilities;\n"),
+            Vec::<usize>::new()
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{info, warn};
+
+use crate::input::ResolvedInput;
+use crate::sources;
+
+/// File generated next to the input CSS; the input is expected to `@import` it.
+pub const COMPONENTS_CSS: &str = "components.css";
+
+const MACRO_CALL: &str = "tw_component!(";
+
+/// Scan the content files for `tw_component!("name", "classes...")` calls and
+/// write a `components.css` with one `@apply` rule per component, next to
+/// the user's own input rather than a generated wrapper.
+///
+/// Does nothing when no components are defined. The file is only rewritten
+/// when its contents change, so tailwind's watcher is not triggered needlessly.
+pub fn generate(manifest_dir: &Path, input: &ResolvedInput) -> Result<Option<PathBuf>> {
+    let input_path = input.source();
+    let mut components = BTreeMap::new();
+    for file in sources::content_files(manifest_dir, input.path()) {
+        if file.extension().is_none_or(|ext| ext != "rs") {
+            continue;
+        }
+        let Ok(source) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for (name, classes) in scan(&source) {
+            if let Some(previous) = components.insert(name.clone(), classes.clone()) {
+                if previous != classes {
+                    warn!(
+                        "Component .{name} is defined more than once with different classes; using the one in {}",
+                        file.display()
+                    );
+                }
+            }
+        }
+    }
+    if components.is_empty() {
+        return Ok(None);
+    }
+
+    let dir = input_path.parent().unwrap_or(manifest_dir);
+    let path = dir.join(COMPONENTS_CSS);
+    let css = render(&components);
+    if fs::read_to_string(&path).ok().as_deref() != Some(css.as_str()) {
+        fs::write(&path, &css).with_context(|| format!("failed to write {}", path.display()))?;
+        info!(
+            "Generated {} component classes in {}",
+            components.len(),
+            path.display()
+        );
+    }
+    let imported = sources::stylesheets(input_path)
+        .iter()
+        .any(|(_, css)| css.contains(COMPONENTS_CSS));
+    if !imported && warn_once(input_path) {
+        warn!(
+            "Add `@import \"./{COMPONENTS_CSS}\";` to {} so tw_component! classes are built",
+            input_path.display()
+        );
+    }
+    Ok(Some(path))
+}
+
+/// Whether the missing-import warning for `input_path` has not been given
+/// yet in this process, so a watch session says it once.
+fn warn_once(input_path: &Path) -> bool {
+    static WARNED: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+    WARNED
+        .lock()
+        .map_or(true, |mut warned| warned.insert(input_path.to_path_buf()))
+}
+
+fn render(components: &BTreeMap<String, String>) -> String {
+    let mut css = String::from(
+        "/* Generated by xilem-web-tailwindcss from tw_component! calls; do not edit. */\n",
+    );
+    for (name, classes) in components {
+        let _ = write!(css, "\n.{name} {{\n  @apply {classes};\n}}\n");
+    }
+    css
+}
+
+/// Find `tw_component!` calls whose arguments are all string literals.
+fn scan(source: &str) -> Vec<(String, String)> {
+    let mut components = Vec::new();
+    let mut rest = source;
+    while let Some(start) = rest.find(MACRO_CALL) {
+        rest = &rest[start + MACRO_CALL.len()..];
+        let mut literals = Vec::new();
+        let mut args = rest;
+        loop {
+            args = args.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+            if args.starts_with(')') {
+                break;
+            }
+            let Some((literal, after)) = string_literal(args) else {
+                literals.clear();
+                break;
+            };
+            literals.push(literal);
+            args = after;
+        }
+        let Some((name, classes)) = literals.split_first() else {
+            continue;
+        };
+        let classes = classes
+            .iter()
+            .flat_map(|classes| classes.split_whitespace())
+            .collect::<Vec<_>>()
+            .join(" ");
+        if is_class_name(name) && !classes.is_empty() {
+            components.push((name.clone(), classes));
+        }
+    }
+    components
+}
+
+/// Parse a leading `"..."` or `r#"..."#` literal.
+pub fn string_literal(input: &str) -> Option<(String, &str)> {
+    if let Some(raw) = input.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let body = raw[hashes..].strip_prefix('"')?;
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        let end = body.find(&terminator)?;
+        return Some((body[..end].to_string(), &body[end + terminator.len()..]));
+    }
+    let body = input.strip_prefix('"')?;
+    let mut value = String::new();
+    let mut chars = body.char_indices();
+    while let Some((index, ch)) = chars.next() {
+        match ch {
+            '"' => return Some((value, &body[index + 1..])),
+            '\\' => {
+                let (_, escaped) = chars.next()?;
+                match escaped {
+                    'n' | 't' | '\n' => value.push(' '),
+                    other => value.push(other),
+                }
+            }
+            other => value.push(other),
+        }
+    }
+    None
+}
+
+fn is_class_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::scan;
+
+    #[test]
+    fn scans_literal_component_calls() {
+        let source = r##"
+            let a = tw_component!("btn", "px-4  py-2\n rounded", r#"bg-blue-600"#);
+            let b = tw_component!(
+                "card-body",
+                "p-6",
+            );
+            let skipped = tw_component!(NAME, "p-2");
+            let invalid = tw_component!("not a class", "p-2");
+        "##;
+        assert_eq!(
+            scan(source),
+            [
+                (
+                    "btn".to_string(),
+                    "px-4 py-2 rounded bg-blue-600".to_string()
+                ),
+                ("card-body".to_string(), "p-6".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::{Context, Result};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use tracing::{info, warn};
+
+use crate::install;
+
+const BROTLI_MAX_LEVEL: u32 = 11;
+const GZIP_MAX_LEVEL: u32 = 9;
+
+/// Write `<output>.br` and `<output>.gz` next to the generated CSS.
+///
+/// `level` is clamped to each format's range; `None` uses the maximum.
+pub fn write_sidecars(output_path: &Path, level: Option<u32>) -> Result<()> {
+    let css = fs::read(output_path)
+        .with_context(|| format!("failed to read {}", output_path.display()))?;
+
+    let brotli_level = level.map_or(BROTLI_MAX_LEVEL, |l| l.min(BROTLI_MAX_LEVEL));
+    let mut brotli_bytes = Vec::new();
+    {
+        let mut writer = brotli::CompressorWriter::new(&mut brotli_bytes, 4096, brotli_level, 22);
+        writer
+            .write_all(&css)
+            .context("failed to brotli-compress CSS")?;
+    }
+    let brotli_path = install::with_suffix(output_path, "br");
+    fs::write(&brotli_path, &brotli_bytes)
+        .with_context(|| format!("failed to write {}", brotli_path.display()))?;
+
+    let gzip_level = level.map_or(GZIP_MAX_LEVEL, |l| l.min(GZIP_MAX_LEVEL));
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::new(gzip_level));
+    encoder.write_all(&css).context("failed to gzip CSS")?;
+    let gzip_bytes = encoder.finish().context("failed to gzip CSS")?;
+    let gzip_path = install::with_suffix(output_path, "gz");
+    fs::write(&gzip_path, &gzip_bytes)
+        .with_context(|| format!("failed to write {}", gzip_path.display()))?;
+
+    info!(
+        "Compressed {} ({} bytes): brotli {} bytes, gzip {} bytes",
+        output_path.display(),
+        css.len(),
+        brotli_bytes.len(),
+        gzip_bytes.len()
+    );
+    Ok(())
+}
+
+/// Static hosts prefer pre-compressed files, so sidecars left over from an
+/// earlier `--compress` build would shadow the fresh CSS.
+pub fn warn_if_stale_sidecars(output_path: &Path) {
+    for ext in ["br", "gz"] {
+        let path = install::with_suffix(output_path, ext);
+        if path.exists() {
+            warn!(
+                "{} is now stale; rebuild with --compress or remove it",
+                path.display()
+            );
+        }
+    }
+}
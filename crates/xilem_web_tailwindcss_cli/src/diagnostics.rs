@@ -0,0 +1,203 @@
+use std::fmt;
+use std::path::PathBuf;
+use tracing::{error, warn};
+
+/// Extensions of files tailwind reports problems in.
+const SOURCE_EXTENSIONS: &[&str] = &[".css", ".js", ".cjs", ".mjs", ".ts", ".html", ".rs"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// A warning or error tailwind printed, with the file and line it points at
+/// when the message names one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: Option<PathBuf>,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "severity": self.severity.as_str(),
+            "file": self.file,
+            "line": self.line,
+            "message": self.message,
+        })
+    }
+
+    /// Log through tracing with the location as structured fields.
+    pub fn emit(&self) {
+        let file = self.file.as_ref().map(|file| file.display().to_string());
+        match self.severity {
+            Severity::Warning => warn!(file, line = self.line, "tailwind: {}", self.message),
+            Severity::Error => error!(file, line = self.line, "tailwind: {}", self.message),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}", file.display())?;
+            if let Some(line) = self.line {
+                write!(f, ":{line}")?;
+            }
+            write!(f, ": ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Best-effort parse of tailwind's human-readable stderr.
+///
+/// Tailwind has no machine-readable diagnostics output, so this recognizes
+/// `warn - ...` lines from v3 and `Error: ...`-style lines from both
+/// versions, and pulls a `path:line` or trailing `in path` out of the
+/// message. Other lines (status output, stack frames, hints) are skipped;
+/// the caller still has the raw text for those.
+pub fn parse(stderr: &str) -> Vec<Diagnostic> {
+    stderr.lines().filter_map(parse_line).collect()
+}
+
+/// The lines of `stderr` that [`parse`] does not turn into diagnostics.
+pub fn unparsed(stderr: &str) -> impl Iterator<Item = &str> {
+    stderr.lines().filter(|line| parse_line(line).is_none())
+}
+
+fn parse_line(line: &str) -> Option<Diagnostic> {
+    let line = line.trim();
+    let (severity, message) = if let Some(message) = strip_any(line, &["warn - ", "warn: "]) {
+        (Severity::Warning, message)
+    } else if let Some((kind, message)) = line.split_once(": ") {
+        if !kind.ends_with("Error") && kind != "error" {
+            return None;
+        }
+        (Severity::Error, message)
+    } else {
+        return None;
+    };
+    let message = message.strip_prefix("tailwindcss: ").unwrap_or(message);
+    let (file, line, message) = locate(message);
+    Some(Diagnostic {
+        severity,
+        file,
+        line,
+        message: message.trim().to_string(),
+    })
+}
+
+fn strip_any<'a>(line: &'a str, prefixes: &[&str]) -> Option<&'a str> {
+    prefixes.iter().find_map(|prefix| line.strip_prefix(prefix))
+}
+
+/// Split the location out of a message, as either a leading
+/// `path:line[:column]: ` or a trailing ` in path[:line]`.
+fn locate(message: &str) -> (Option<PathBuf>, Option<usize>, &str) {
+    if let Some((location, rest)) = message.split_once(": ") {
+        if let Some((file, line)) = parse_location(location) {
+            return (Some(file), line, rest);
+        }
+    }
+    if let Some((rest, location)) = message.rsplit_once(" in ") {
+        let location = location
+            .trim_end_matches(['.', ','])
+            .trim_matches(['`', '"', '\'']);
+        if let Some((file, line)) = parse_location(location) {
+            return (Some(file), line, rest);
+        }
+    }
+    (None, None, message)
+}
+
+/// `path`, `path:line` or `path:line:column`, where the path has a source
+/// file extension.
+fn parse_location(location: &str) -> Option<(PathBuf, Option<usize>)> {
+    let mut path = location;
+    let mut line = None;
+    for _ in 0..2 {
+        let Some((head, number)) = path.rsplit_once(':') else {
+            break;
+        };
+        let Ok(number) = number.parse::<usize>() else {
+            break;
+        };
+        path = head;
+        line = Some(number);
+    }
+    if path.contains(char::is_whitespace)
+        || !SOURCE_EXTENSIONS
+            .iter()
+            .any(|extension| path.ends_with(extension))
+    {
+        return None;
+    }
+    Some((PathBuf::from(path), line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Severity, parse, unparsed};
+    use std::path::Path;
+
+    #[test]
+    fn parses_warnings_and_errors_with_locations() {
+        let stderr = "\
+≈ tailwindcss v4.1.5
+warn - The `content` option in your Tailwind CSS configuration is missing or empty.
+warn - No utility classes were detected in tailwind.css
+CssSyntaxError: tailwindcss: /app/tailwind.css:3:5: The `foo` class does not exist.
+    at Input.error (node_modules/postcss/lib/input.js:106:16)
+Error: Cannot apply unknown utility class: bar
+Done in 12ms
+";
+        let diagnostics = parse(stderr);
+        assert_eq!(diagnostics.len(), 4);
+
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].file, None);
+
+        assert_eq!(
+            diagnostics[1].file.as_deref(),
+            Some(Path::new("tailwind.css"))
+        );
+        assert_eq!(diagnostics[1].message, "No utility classes were detected");
+
+        assert_eq!(diagnostics[2].severity, Severity::Error);
+        assert_eq!(
+            diagnostics[2].file.as_deref(),
+            Some(Path::new("/app/tailwind.css"))
+        );
+        assert_eq!(diagnostics[2].line, Some(3));
+        assert_eq!(diagnostics[2].message, "The `foo` class does not exist.");
+
+        assert_eq!(diagnostics[3].line, None);
+        assert_eq!(
+            diagnostics[3].to_string(),
+            "Cannot apply unknown utility class: bar"
+        );
+
+        assert_eq!(
+            unparsed(stderr).collect::<Vec<_>>(),
+            [
+                "≈ tailwindcss v4.1.5",
+                "    at Input.error (node_modules/postcss/lib/input.js:106:16)",
+                "Done in 12ms",
+            ]
+        );
+    }
+}
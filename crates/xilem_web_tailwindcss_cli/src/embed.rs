@@ -0,0 +1,33 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+const SYMBOL: &str = "TAILWIND_CSS";
+
+/// Write a Rust source file defining `pub const TAILWIND_CSS: &str` with the
+/// generated CSS, for `include!`-ing into a single-file deploy.
+pub fn write_rust_module(css_path: &Path, rust_path: &Path) -> Result<()> {
+    let css = fs::read_to_string(css_path)
+        .with_context(|| format!("failed to read {}", css_path.display()))?;
+    if let Some(parent) = rust_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create {}", parent.display()))?;
+    }
+    fs::write(rust_path, rust_module(&css))
+        .with_context(|| format!("failed to write {}", rust_path.display()))?;
+    info!(
+        "Wrote {SYMBOL} ({} bytes) to {}",
+        css.len(),
+        rust_path.display()
+    );
+    Ok(())
+}
+
+/// `str`'s `Debug` output is a valid Rust string literal: quotes, backslashes
+/// and control characters are all escaped.
+fn rust_module(css: &str) -> String {
+    format!(
+        "// @generated by xilem-web-tailwindcss; do not edit.\n\npub const {SYMBOL}: &str = {css:?};\n"
+    )
+}
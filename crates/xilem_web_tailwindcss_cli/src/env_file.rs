@@ -0,0 +1,120 @@
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Read `KEY=VALUE` pairs from a `.env` file.
+pub fn load(path: &Path) -> Result<Vec<(String, String)>> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let vars = parse(&contents).with_context(|| format!("invalid env file {}", path.display()))?;
+    info!("Loaded {} variables from {}", vars.len(), path.display());
+    Ok(vars)
+}
+
+/// Parse dotenv syntax: blank lines and `#` comments are skipped, an
+/// `export ` prefix is allowed, double-quoted values support `\n`, `\"` and
+/// `\\` escapes, single-quoted values are literal, and unquoted values end at
+/// ` #`.
+fn parse(contents: &str) -> Result<Vec<(String, String)>> {
+    let mut vars = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow!("line {}: expected KEY=VALUE", index + 1))?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(anyhow!("line {}: invalid variable name `{key}`", index + 1));
+        }
+        let value = parse_value(value.trim())
+            .ok_or_else(|| anyhow!("line {}: unterminated quote", index + 1))?;
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+fn parse_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        return rest.find('\'').map(|end| rest[..end].to_string());
+    }
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '"' => return Some(value),
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    other => value.push(other),
+                },
+                other => value.push(other),
+            }
+        }
+        return None;
+    }
+    let value = raw.find(" #").map_or(raw, |end| &raw[..end]);
+    Some(value.trim_end().to_string())
+}
+
+/// Parse a `--env KEY=VALUE` argument.
+pub fn parse_assignment(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((key, value)) if !key.is_empty() => Ok((key.to_string(), value.to_string())),
+        _ => Err(format!("expected KEY=VALUE, got `{arg}`")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, parse_assignment};
+
+    #[test]
+    fn parses_dotenv_syntax() {
+        let contents = r#"
+# a comment
+
+export NODE_ENV=production
+PLAIN = value with spaces # trailing comment
+HASH=a#b
+DOUBLE="line\nnext \"quoted\" back\\slash # kept"
+SINGLE='literal \n # kept'
+EMPTY=
+"#;
+        let vars = parse(contents).unwrap();
+        let expected = [
+            ("NODE_ENV", "production"),
+            ("PLAIN", "value with spaces"),
+            ("HASH", "a#b"),
+            ("DOUBLE", "line\nnext \"quoted\" back\\slash # kept"),
+            ("SINGLE", "literal \\n # kept"),
+            ("EMPTY", ""),
+        ];
+        assert_eq!(vars.len(), expected.len());
+        for ((key, value), (expected_key, expected_value)) in vars.iter().zip(expected) {
+            assert_eq!(key, expected_key);
+            assert_eq!(value, expected_value);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_lines() {
+        let err = parse("OK=1\nNOT AN ASSIGNMENT").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: expected KEY=VALUE");
+        let err = parse("MY KEY=1").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: invalid variable name `MY KEY`");
+        let err = parse("QUOTED=\"open").unwrap_err();
+        assert_eq!(err.to_string(), "line 1: unterminated quote");
+
+        assert_eq!(
+            parse_assignment("KEY=a=b"),
+            Ok(("KEY".to_string(), "a=b".to_string()))
+        );
+        assert!(parse_assignment("=value").is_err());
+    }
+}
@@ -0,0 +1,376 @@
+use anyhow::{Context, Result, anyhow};
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, IsTerminal as _, Read as _};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{info, warn};
+
+use crate::compat;
+use crate::metadata;
+use crate::sources;
+
+/// Where the tailwind input CSS comes from.
+#[derive(Debug, Clone)]
+pub enum InputSource {
+    /// `tailwind.css` in the manifest directory.
+    Autodetect,
+    /// `--input`, which may be a glob matching exactly one file.
+    Path(PathBuf),
+    /// `--input-inline` CSS text, or CSS read from stdin.
+    Inline(String),
+}
+
+impl InputSource {
+    /// `--stdin` and `--input -` read the whole of stdin up front.
+    pub fn from_args(input: Option<PathBuf>, inline: Option<String>, stdin: bool) -> Result<Self> {
+        if stdin || input.as_deref() == Some(Path::new("-")) {
+            return read_stdin().map(Self::Inline);
+        }
+        Ok(match (input, inline) {
+            (_, Some(css)) => Self::Inline(css),
+            (Some(path), None) => Self::Path(path),
+            (None, None) => Self::Autodetect,
+        })
+    }
+}
+
+fn read_stdin() -> Result<String> {
+    let mut stdin = io::stdin().lock();
+    if stdin.is_terminal() {
+        info!("Reading the tailwind input CSS from stdin; end it with Ctrl-D");
+    }
+    let mut css = String::new();
+    stdin
+        .read_to_string(&mut css)
+        .context("failed to read the tailwind input from stdin")?;
+    Ok(css)
+}
+
+/// A concrete input file for tailwind.
+///
+/// Inline input is written to a temporary file that is removed when this is
+/// dropped, so keep it alive for as long as tailwind runs.
+#[derive(Debug)]
+pub struct ResolvedInput {
+    path: PathBuf,
+    source: PathBuf,
+    cleanup: Vec<TempInput>,
+}
+
+impl ResolvedInput {
+    /// The stylesheet to hand to tailwind, which may be a generated one.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The user's own stylesheet, before any generated wrapper for metadata
+    /// `sources` or a config. Files next to the input belong next to this.
+    pub fn source(&self) -> &Path {
+        &self.source
+    }
+
+    /// Switch to a generated stylesheet that imports this input and then
+    /// loads `config`: a JavaScript config with `@config`, which is how
+    /// tailwind v4 takes one, and a CSS config with `@import`.
+    pub fn import_config(&mut self, config: &Path) -> Result<()> {
+        let directive = if is_css(config) { "@import" } else { "@config" };
+        let css = format!(
+            "@import \"{}\";\n{directive} \"{}\";\n",
+            css_path(&self.path),
+            css_path(config)
+        );
+        let temp = write_generated("tailwind-config", "css", &css)?;
+        self.path.clone_from(&temp.0);
+        self.cleanup.push(temp);
+        Ok(())
+    }
+}
+
+/// Whether a tailwind config is a CSS file (v4's `@theme` and friends)
+/// rather than a JavaScript one.
+pub fn is_css(config: &Path) -> bool {
+    config.extension().is_some_and(|ext| ext == "css")
+}
+
+#[derive(Debug)]
+struct TempInput(PathBuf);
+
+impl Drop for TempInput {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Resolve any input source to a file path tailwind can read.
+///
+/// The path may not exist for `Autodetect` and plain `Path` sources; callers
+/// report that as a missing input.
+///
+/// Content roots from `[package.metadata.xilem-web-tailwindcss] sources` are
+/// added with a generated stylesheet that imports the input.
+pub fn resolve(manifest_dir: &Path, source: &InputSource) -> Result<ResolvedInput> {
+    let (path, mut cleanup) = match source {
+        InputSource::Autodetect => (manifest_dir.join("tailwind.css"), Vec::new()),
+        InputSource::Path(path) if is_glob(path) => (expand_glob(manifest_dir, path)?, Vec::new()),
+        InputSource::Path(path) => (resolve_input_path(manifest_dir, path), Vec::new()),
+        InputSource::Inline(css) => {
+            let temp = write_inline(manifest_dir, css)?;
+            (temp.0.clone(), vec![temp])
+        }
+    };
+    let source = path;
+    let path = match with_metadata_sources(manifest_dir, &source)? {
+        Some(temp) => {
+            let generated = temp.0.clone();
+            cleanup.push(temp);
+            generated
+        }
+        None => source.clone(),
+    };
+    Ok(ResolvedInput {
+        path,
+        source,
+        cleanup,
+    })
+}
+
+/// Write a stylesheet that imports `input_path` and adds an `@source` for
+/// every existing root listed in `Cargo.toml`. Tailwind v3 has no `@source`,
+/// so its roots belong in the config's `content` array instead.
+fn with_metadata_sources(manifest_dir: &Path, input_path: &Path) -> Result<Option<TempInput>> {
+    let roots = metadata::sources(manifest_dir)?;
+    if roots.is_empty() || !input_path.is_file() {
+        return Ok(None);
+    }
+    let major = sources::stylesheets(input_path)
+        .iter()
+        .find_map(|(_, css)| compat::input_major(css));
+    if major == Some(3) {
+        warn!(
+            "Ignoring `sources` in Cargo.toml: {} uses tailwind v3, which has no `@source`; add the roots to the `content` array of tailwind.config.js",
+            input_path.display()
+        );
+        return Ok(None);
+    }
+    let mut css = format!("@import \"{}\";\n", css_path(input_path));
+    for root in roots {
+        let path = manifest_dir.join(&root);
+        if !path.exists() {
+            warn!("Content source {root} from Cargo.toml does not exist");
+            continue;
+        }
+        let _ = writeln!(css, "@source \"{}\";", css_path(&path));
+    }
+    write_generated("tailwind-sources", "css", &css).map(Some)
+}
+
+/// An absolute path with forward slashes, for a directive in a generated
+/// stylesheet that does not live next to the file it points at.
+fn css_path(path: &Path) -> String {
+    let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// The tailwind config file from `--tailwind-config` or `--config-inline`,
+/// if either was given.
+pub fn resolve_config(
+    manifest_dir: &Path,
+    path: Option<&Path>,
+    inline: Option<&str>,
+) -> Result<Option<ResolvedInput>> {
+    let resolved = match (path, inline) {
+        (_, Some(config)) => {
+            let ext = inline_config_extension(config);
+            let temp = write_generated("tailwind-config-inline", ext, config)?;
+            ResolvedInput {
+                path: temp.0.clone(),
+                source: temp.0.clone(),
+                cleanup: vec![temp],
+            }
+        }
+        (Some(path), None) => {
+            let path = resolve_input_path(manifest_dir, path);
+            ResolvedInput {
+                source: path.clone(),
+                path,
+                cleanup: Vec::new(),
+            }
+        }
+        (None, None) => return Ok(None),
+    };
+    Ok(Some(resolved))
+}
+
+/// `--config-inline` takes a JavaScript config or a CSS one. Text that
+/// exports anything or calls `require` is JavaScript; the rest is CSS.
+fn inline_config_extension(config: &str) -> &'static str {
+    let javascript = [
+        "module.exports",
+        "export default",
+        "export const",
+        "require(",
+    ]
+    .iter()
+    .any(|marker| config.contains(marker));
+    if javascript { "js" } else { "css" }
+}
+
+/// Inline CSS is written next to Cargo.toml so relative `@source` and
+/// `@import` paths resolve as they would from `tailwind.css`.
+fn write_inline(manifest_dir: &Path, css: &str) -> Result<TempInput> {
+    let path = manifest_dir.join(format!(".{}", temp_name("tailwind-inline", "css")));
+    write_temp(path, css)
+}
+
+/// Files that only point at other files, and inline configs, are written to
+/// the system temp dir, so one left behind never lands in the project, where
+/// an `--input` glob such as `*.css` would match it.
+fn write_generated(stem: &str, ext: &str, contents: &str) -> Result<TempInput> {
+    let path = env::temp_dir().join(format!("xilem-web-{}", temp_name(stem, ext)));
+    write_temp(path, contents)
+}
+
+/// A file name unique to this write. Builds of several workspace members run
+/// in one process, so the name carries a counter as well as the pid.
+fn temp_name(stem: &str, ext: &str) -> String {
+    static WRITTEN: AtomicUsize = AtomicUsize::new(0);
+    let index = WRITTEN.fetch_add(1, Ordering::Relaxed);
+    format!("{stem}-{}-{index}.{ext}", std::process::id())
+}
+
+fn write_temp(path: PathBuf, contents: &str) -> Result<TempInput> {
+    fs::write(&path, contents).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(TempInput(path))
+}
+
+fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+fn expand_glob(manifest_dir: &Path, pattern: &Path) -> Result<PathBuf> {
+    let pattern = if pattern.is_absolute() {
+        pattern.to_path_buf()
+    } else {
+        manifest_dir.join(pattern)
+    };
+    let pattern = pattern.to_string_lossy();
+    let mut matches = glob::glob(&pattern)
+        .with_context(|| format!("invalid input glob {pattern}"))?
+        .filter_map(Result::ok)
+        .filter(|path| path.is_file());
+    let first = matches
+        .next()
+        .ok_or_else(|| anyhow!("input glob {pattern} matched no files"))?;
+    if let Some(second) = matches.next() {
+        return Err(anyhow!(
+            "input glob {pattern} matched more than one file ({} and {}); pass a single file",
+            first.display(),
+            second.display()
+        ));
+    }
+    Ok(first)
+}
+
+fn resolve_input_path(manifest_dir: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    let manifest_candidate = manifest_dir.join(path);
+    if manifest_candidate.exists() {
+        return manifest_candidate;
+    }
+
+    if let Ok(cwd) = env::current_dir() {
+        let cwd_candidate = cwd.join(path);
+        if cwd_candidate.exists() {
+            return cwd_candidate;
+        }
+    }
+
+    manifest_candidate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InputSource, inline_config_extension, resolve, resolve_config};
+    use crate::sources;
+    use std::fs;
+
+    #[test]
+    fn metadata_sources_are_added_to_a_generated_input() {
+        let dir = std::env::temp_dir().join(format!("xilem-tw-sources-{}", std::process::id()));
+        fs::create_dir_all(dir.join("shared")).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\n[package.metadata.xilem-web-tailwindcss]\nsources = [\"shared\", \"missing\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("tailwind.css"),
+            "@import \"tailwindcss\";\n@source \"./src\";\n",
+        )
+        .unwrap();
+
+        let input = resolve(&dir, &InputSource::Autodetect).unwrap();
+        let generated = fs::read_to_string(input.path()).unwrap();
+        assert!(!input.path().starts_with(&dir));
+        assert_eq!(input.source(), dir.join("tailwind.css"));
+        let imported = fs::canonicalize(dir.join("tailwind.css")).unwrap();
+        assert!(generated.starts_with(&format!("@import \"{}\";\n", imported.display())));
+        assert!(generated.contains("/shared\";"), "{generated}");
+        assert!(!generated.contains("missing"));
+
+        let roots: Vec<_> = sources::discover(&dir, input.path())
+            .iter()
+            .map(sources::ContentSource::root)
+            .collect();
+        assert_eq!(roots.len(), 2);
+        assert!(roots.iter().any(|root| root.ends_with("shared")));
+        assert!(roots.iter().any(|root| root.ends_with("src")));
+
+        let generated_path = input.path().to_path_buf();
+        drop(input);
+        assert!(!generated_path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn configs_are_imported_by_kind() {
+        assert_eq!(
+            inline_config_extension("module.exports = { content: [] }"),
+            "js"
+        );
+        assert_eq!(
+            inline_config_extension("export default { theme: {} }"),
+            "js"
+        );
+        assert_eq!(
+            inline_config_extension("@theme { --color-brand: #123456; }"),
+            "css"
+        );
+
+        let dir = std::env::temp_dir().join(format!("xilem-tw-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("tailwind.css"), "@import \"tailwindcss\";\n").unwrap();
+        for (inline, directive) in [
+            ("module.exports = {}", "@config"),
+            ("@theme { --color-brand: red; }", "@import"),
+        ] {
+            let config = resolve_config(&dir, None, Some(inline)).unwrap().unwrap();
+            assert!(!config.path().starts_with(&dir));
+            let mut input = resolve(&dir, &InputSource::Autodetect).unwrap();
+            input.import_config(config.path()).unwrap();
+            assert_eq!(input.source(), dir.join("tailwind.css"));
+            let generated = fs::read_to_string(input.path()).unwrap();
+            let config_path = fs::canonicalize(config.path()).unwrap();
+            assert!(
+                generated.ends_with(&format!("{directive} \"{}\";\n", config_path.display())),
+                "{generated}"
+            );
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// A lock older than this is assumed to be left over from a killed process.
+const STALE_LOCK_AFTER: Duration = Duration::from_secs(10 * 60);
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Run `install` to create `binary_path` unless another process (or thread)
+/// already did, holding `<binary>.lock` for the duration.
+///
+/// `install` writes to a temporary path that is renamed into place, so a
+/// concurrent reader never sees a partial binary.
+pub fn install_once(binary_path: &Path, install: impl FnOnce(&Path) -> Result<()>) -> Result<()> {
+    if let Some(parent) = binary_path.parent() {
+        fs::create_dir_all(parent).context("failed to create tailwindcss directory")?;
+    }
+    let _lock = InstallLock::acquire(binary_path)?;
+    if binary_path.exists() {
+        return Ok(());
+    }
+
+    let temp_path = with_suffix(binary_path, &format!("{}.tmp", std::process::id()));
+    let result = install(&temp_path).and_then(|()| {
+        fs::rename(&temp_path, binary_path).context("failed to move tailwindcss into place")
+    });
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    result
+}
+
+#[derive(Debug)]
+struct InstallLock(PathBuf);
+
+impl InstallLock {
+    fn acquire(binary_path: &Path) -> Result<Self> {
+        let path = with_suffix(binary_path, "lock");
+        let mut announced = false;
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(_) => return Ok(Self(path)),
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        warn!("Removing stale install lock {}", path.display());
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if !announced {
+                        info!("Waiting for another tailwindcss install to finish");
+                        announced = true;
+                    }
+                    thread::sleep(LOCK_POLL_INTERVAL);
+                }
+                Err(err) => {
+                    return Err(err)
+                        .with_context(|| format!("failed to create {}", path.display()));
+                }
+            }
+        }
+    }
+}
+
+impl Drop for InstallLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > STALE_LOCK_AFTER)
+}
+
+pub fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(".");
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::install_once;
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn concurrent_installs_download_once() {
+        let dir = std::env::temp_dir().join(format!("xilem-tw-install-{}", std::process::id()));
+        let binary = dir.join("tailwindcss-v4.1.5");
+        let downloads = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| {
+                    install_once(&binary, |temp| {
+                        downloads.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(200));
+                        fs::write(temp, b"binary")?;
+                        Ok(())
+                    })
+                    .unwrap();
+                });
+            }
+        });
+
+        assert_eq!(downloads.load(Ordering::SeqCst), 1);
+        assert_eq!(fs::read(&binary).unwrap(), b"binary");
+        assert_eq!(
+            fs::read_dir(&dir).unwrap().count(),
+            1,
+            "lock or temp file left"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::{Context, Result, anyhow};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use xilem_web_tailwindcss::{__TW_ARGUMENT_KEYWORDS, is_known_utility, split_variants};
+
+use crate::components::string_literal;
+
+const MACRO_CALL: &str = "tw!(";
+
+/// A comment that sets the length limit for the literals after it, e.g.
+/// `// tw-lint: max-length = 200`.
+const MAX_LENGTH_DIRECTIVE: &str = "tw-lint: max-length";
+
+/// The default `--max-length`; longer tokens are almost always a pasted URL or
+/// a whole attribute.
+pub const DEFAULT_MAX_LENGTH: usize = 80;
+
+/// A class in a `tw!` literal that the lint reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoundClass {
+    pub path: PathBuf,
+    pub line: usize,
+    pub class: String,
+}
+
+/// The result of scanning a set of source files.
+#[derive(Debug, Default)]
+pub struct LintReport {
+    pub files: usize,
+    pub unknown: Vec<FoundClass>,
+    /// Tokens longer than the length limit in effect at their line.
+    pub too_long: Vec<FoundClass>,
+    /// Safelisted classes that no scanned literal references.
+    pub unreferenced: Vec<String>,
+}
+
+/// The `.rs` files matched by `patterns`, relative to `manifest_dir`.
+pub fn expand(manifest_dir: &Path, patterns: &[String]) -> Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    for pattern in patterns {
+        let pattern = if Path::new(pattern).is_absolute() {
+            PathBuf::from(pattern)
+        } else {
+            manifest_dir.join(pattern)
+        };
+        let pattern = pattern.to_string_lossy();
+        let before = files.len();
+        files.extend(
+            glob::glob(&pattern)
+                .with_context(|| format!("invalid lint glob {pattern}"))?
+                .filter_map(Result::ok)
+                .filter(|path| path.is_file()),
+        );
+        if files.len() == before {
+            return Err(anyhow!("lint glob {pattern} matched no files"));
+        }
+    }
+    Ok(files)
+}
+
+/// Check every `tw!` literal class in `files` against the known utility
+/// patterns, treating classes in the `safelist` file as known, and against
+/// `max_length`.
+pub fn run(
+    files: &BTreeSet<PathBuf>,
+    safelist: Option<&Path>,
+    max_length: usize,
+) -> Result<LintReport> {
+    let safelist = match safelist {
+        Some(path) => read_safelist(path)?,
+        None => BTreeSet::new(),
+    };
+    let mut report = LintReport::default();
+    let mut referenced = BTreeSet::new();
+    for path in files
+        .iter()
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rs"))
+    {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        report.files += 1;
+        let limits = length_directives(&source);
+        for (line, class) in scan(&source) {
+            let found = FoundClass {
+                path: path.clone(),
+                line,
+                class: class.clone(),
+            };
+            let limit = limits
+                .iter()
+                .rev()
+                .find(|(directive_line, _)| *directive_line < line)
+                .map_or(max_length, |(_, limit)| *limit);
+            let (_, utility) = split_variants(&class);
+            if class.chars().count() > limit {
+                report.too_long.push(found);
+            } else if !safelist.contains(&class) && !is_known_utility(utility) {
+                report.unknown.push(found);
+            }
+            referenced.insert(class);
+        }
+    }
+    report.unreferenced = safelist.difference(&referenced).cloned().collect();
+    Ok(report)
+}
+
+/// The `// tw-lint: max-length = N` comments of a file, with their lines.
+fn length_directives(source: &str) -> Vec<(usize, usize)> {
+    source
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let (_, comment) = line.split_once("//")?;
+            let value = comment.trim().strip_prefix(MAX_LENGTH_DIRECTIVE)?;
+            let limit = value.trim().strip_prefix('=')?.trim().parse().ok()?;
+            Some((index + 1, limit))
+        })
+        .collect()
+}
+
+/// One class per whitespace-separated word; `#` starts a comment.
+fn read_safelist(path: &Path) -> Result<BTreeSet<String>> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("failed to read safelist {}", path.display()))?;
+    Ok(contents
+        .lines()
+        .map(|line| line.split_once('#').map_or(line, |(classes, _)| classes))
+        .flat_map(str::split_whitespace)
+        .map(str::to_string)
+        .collect())
+}
+
+/// Find the classes in string literals inside `tw!(...)` calls, with the
+/// line each literal starts on.
+///
+/// Literals containing `{` are format strings and are skipped, as are the
+/// arguments of `remove_variant`, `scale` and `pick` and literal `match`
+/// patterns, which are not classes.
+fn scan(source: &str) -> Vec<(usize, String)> {
+    let mut classes = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = source[offset..].find(MACRO_CALL) {
+        let start = offset + found;
+        offset = start + MACRO_CALL.len();
+        if source[..start].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let mut depth = 1_usize;
+        while depth > 0 {
+            let Some(index) = source[offset..].find(['(', ')', '"']) else {
+                break;
+            };
+            let at = offset + index;
+            match source.as_bytes()[at] {
+                b'(' => {
+                    depth += 1;
+                    offset = at + 1;
+                }
+                b')' => {
+                    depth -= 1;
+                    offset = at + 1;
+                }
+                _ => {
+                    let literal_start = raw_literal_start(source, at);
+                    let Some((value, rest)) = string_literal(&source[literal_start..]) else {
+                        break;
+                    };
+                    offset = source.len() - rest.len();
+                    if value.contains('{')
+                        || rest.trim_start().starts_with("=>")
+                        || is_keyword_argument(&source[..literal_start])
+                    {
+                        continue;
+                    }
+                    let line = source[..literal_start].matches('\n').count() + 1;
+                    classes.extend(
+                        value
+                            .split_whitespace()
+                            .map(|class| (line, class.to_string())),
+                    );
+                }
+            }
+        }
+    }
+    classes
+}
+
+/// Whether the argument ending at `before` starts with a keyword such as
+/// `scale`, whose literal is not a class list.
+fn is_keyword_argument(before: &str) -> bool {
+    let argument = before
+        .rfind([',', '(', '[', '{', '>'])
+        .map_or(before, |at| &before[at + 1..]);
+    argument
+        .split_whitespace()
+        .next()
+        .is_some_and(|word| __TW_ARGUMENT_KEYWORDS.contains(&word))
+}
+
+/// Step back from the opening quote over the `r#` of a raw string literal.
+fn raw_literal_start(source: &str, quote: usize) -> usize {
+    let before = source[..quote].trim_end_matches('#');
+    match before.strip_suffix('r') {
+        Some(prefix) if !prefix.ends_with(|c: char| c.is_alphanumeric() || c == '_') => {
+            prefix.len()
+        }
+        _ => quote,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{length_directives, scan};
+
+    #[test]
+    fn scans_tw_literals_with_lines() {
+        let source = r##"
+let a = tw!("p-4 flx", if active => "bg-red-500");
+let b = tw!(
+    r#"hover:textt-sm"#,
+    format!("col-span-{n}"),
+    repeat 2 => |i| classes(i),
+    remove_variant "dark",
+    scale "p" => 4,
+    pick "ab".len() => ["bg-red-50"],
+    match size { "sm" => "text-sm", _ => "text-base" },
+);
+let c = my_tw!("ignored");
+"##;
+        assert_eq!(
+            scan(source),
+            [
+                (2, "p-4".to_string()),
+                (2, "flx".to_string()),
+                (2, "bg-red-500".to_string()),
+                (4, "hover:textt-sm".to_string()),
+                (9, "bg-red-50".to_string()),
+                (10, "text-sm".to_string()),
+                (10, "text-base".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_length_directives() {
+        let source = "// tw-lint: max-length = 200\nlet x = 1; // tw-lint: max-length=40\n// tw-lint: max-length\n";
+        assert_eq!(length_directives(source), [(1, 200), (2, 40)]);
+    }
+}
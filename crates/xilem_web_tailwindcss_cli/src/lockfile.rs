@@ -0,0 +1,144 @@
+use anyhow::{Context, Result, anyhow};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const LOCKFILE_NAME: &str = "tailwind.lock";
+
+/// The contents of `tailwind.lock`: the resolved release tag and the SHA-256
+/// of its binary for each platform a teammate has installed it on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lockfile {
+    pub version: String,
+    pub sha256: BTreeMap<String, String>,
+}
+
+impl Lockfile {
+    pub fn path(manifest_dir: &Path) -> PathBuf {
+        manifest_dir.join(LOCKFILE_NAME)
+    }
+
+    pub fn read(manifest_dir: &Path) -> Result<Option<Self>> {
+        let path = Self::path(manifest_dir);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        Self::parse(&contents)
+            .with_context(|| format!("invalid {}", path.display()))
+            .map(Some)
+    }
+
+    fn parse(contents: &str) -> Result<Self> {
+        let value: serde_json::Value = serde_json::from_str(contents)?;
+        let version = value["version"]
+            .as_str()
+            .ok_or_else(|| anyhow!("missing `version`"))?
+            .to_string();
+        let sha256 = value["sha256"]
+            .as_object()
+            .ok_or_else(|| anyhow!("missing `sha256`"))?
+            .iter()
+            .map(|(platform, hash)| {
+                hash.as_str()
+                    .map(|hash| (platform.clone(), hash.to_string()))
+                    .ok_or_else(|| anyhow!("checksum for {platform} is not a string"))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { version, sha256 })
+    }
+
+    pub fn write(&self, manifest_dir: &Path) -> Result<()> {
+        let path = Self::path(manifest_dir);
+        let value = serde_json::json!({
+            "version": self.version,
+            "sha256": self.sha256,
+        });
+        let mut contents = serde_json::to_string_pretty(&value)?;
+        contents.push('\n');
+        fs::write(&path, contents).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+/// Lowercase hex SHA-256 of a file.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    Ok(sha256_hex(&bytes))
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(64);
+    for byte in Sha256::digest(bytes) {
+        let _ = write!(hex, "{byte:02x}");
+    }
+    hex
+}
+
+/// The hash listed for `asset` in a `sha256sum`-style file, whose lines are
+/// `<hex>  <name>` with the name optionally prefixed by `./` or `*`.
+pub fn find_checksum<'a>(sums: &'a str, asset: &str) -> Option<&'a str> {
+    sums.lines().find_map(|line| {
+        let (hash, name) = line.trim().split_once(char::is_whitespace)?;
+        let name = name.trim_start();
+        let name = name.strip_prefix('*').unwrap_or(name);
+        let name = name.strip_prefix("./").unwrap_or(name);
+        (name == asset).then_some(hash)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Lockfile, find_checksum, sha256_file};
+    use std::fs;
+
+    #[test]
+    fn lockfile_round_trips() {
+        let dir = std::env::temp_dir().join(format!("xilem-tw-lockfile-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        assert_eq!(Lockfile::read(&dir).unwrap(), None);
+
+        let binary = dir.join("tailwindcss");
+        fs::write(&binary, "abc").unwrap();
+        let hash = sha256_file(&binary).unwrap();
+        assert_eq!(
+            hash,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let lock = Lockfile {
+            version: "v4.1.5".to_string(),
+            sha256: [("tailwindcss-linux-x64".to_string(), hash)].into(),
+        };
+        lock.write(&dir).unwrap();
+        assert_eq!(Lockfile::read(&dir).unwrap(), Some(lock));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn finds_release_checksums() {
+        let sums = "\
+0123abcd  ./tailwindcss-linux-arm64
+4567ef01  ./tailwindcss-linux-x64
+89ab2345 *tailwindcss-windows-x64.exe
+cdef6789  ./tailwindcss-linux-x64.tar.gz
+";
+        assert_eq!(
+            find_checksum(sums, "tailwindcss-linux-x64"),
+            Some("4567ef01")
+        );
+        assert_eq!(
+            find_checksum(sums, "tailwindcss-windows-x64.exe"),
+            Some("89ab2345")
+        );
+        assert_eq!(
+            find_checksum(sums, "tailwindcss-linux-x64.tar.gz"),
+            Some("cdef6789")
+        );
+        assert_eq!(find_checksum(sums, "tailwindcss-linux-x64-musl"), None);
+    }
+}
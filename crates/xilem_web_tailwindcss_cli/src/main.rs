@@ -1,29 +1,81 @@
 use anyhow::{Context, Result, anyhow};
-use clap::{Args, Parser, Subcommand};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use std::env;
+use std::io::IsTerminal as _;
+use std::num::NonZeroUsize;
 use std::path::{Path, PathBuf};
 use std::process::{Child, Command as ProcessCommand, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 use std::time::Duration;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use tracing_subscriber::EnvFilter;
 
+mod ansi;
+mod archive;
+mod color_map;
+mod compat;
+mod components;
+mod compress;
+mod diagnostics;
+mod embed;
+mod env_file;
+mod input;
+mod install;
+mod lint;
+mod lockfile;
+mod metadata;
+mod output;
+mod sources;
+mod tagged;
 mod tailwind;
+mod watch;
+mod workspace;
 
+use ansi::ColorChoice;
+use diagnostics::Diagnostic;
+use input::InputSource;
+use tagged::Tag;
 use tailwind::{CliSettings, TailwindCli};
+use watch::{InitialBuild, WatchOptions};
 
 #[derive(Parser, Debug)]
 #[command(name = "xilem-web-tailwindcss")]
 #[command(about = "TailwindCSS helper for xilem_web projects")]
+#[allow(clippy::struct_excessive_bools)]
 struct Cli {
-    /// Path to Cargo.toml or project directory.
+    /// Path to Cargo.toml or project directory; `build` also accepts a glob
+    /// such as `crates/*/Cargo.toml`.
     #[arg(long, global = true)]
     manifest_path: Option<PathBuf>,
 
-    /// Path to the tailwind input CSS file.
+    /// Path to the tailwind input CSS file; `-` reads it from stdin.
     #[arg(long, short = 'i', global = true)]
     input: Option<PathBuf>,
 
-    /// Path to the generated tailwind output CSS file.
+    /// Tailwind input CSS given directly, instead of a file.
+    #[arg(long, global = true, value_name = "CSS", conflicts_with = "input")]
+    input_inline: Option<String>,
+
+    /// Read the tailwind input CSS from stdin; same as `--input -`.
+    #[arg(long, global = true, conflicts_with_all = ["input", "input_inline"])]
+    stdin: bool,
+
+    /// Tailwind config file: JavaScript, or CSS for tailwind v4.
+    #[arg(long, global = true, value_name = "PATH")]
+    tailwind_config: Option<PathBuf>,
+
+    /// Tailwind config given directly, instead of a file.
+    #[arg(
+        long,
+        global = true,
+        value_name = "CONFIG",
+        conflicts_with = "tailwind_config"
+    )]
+    config_inline: Option<String>,
+
+    /// Path to the generated tailwind output CSS file; `-` writes it to
+    /// stdout (`build` only).
     #[arg(long, short = 'o', global = true)]
     output: Option<PathBuf>,
 
@@ -35,10 +87,56 @@ struct Cli {
     #[arg(long, global = true)]
     no_downloads: bool,
 
+    /// Fail instead of creating or updating `tailwind.lock`.
+    #[arg(long, global = true)]
+    frozen: bool,
+
+    /// Skip checking downloaded binaries against the release's `sha256sums.txt`.
+    #[arg(long, global = true)]
+    no_verify: bool,
+
+    /// Remove repeated `@import "tailwindcss"`/`@tailwind` lines from the
+    /// input before building, keeping the original as `<input>.bak`.
+    #[arg(long, global = true)]
+    fix_input: bool,
+
+    /// GitHub `owner/repo` to download tailwindcss releases from.
+    #[arg(long, global = true)]
+    release_repo: Option<String>,
+
+    /// GitHub token for release lookups and downloads; defaults to
+    /// `GITHUB_TOKEN` or `GH_TOKEN`.
+    #[arg(long, global = true, value_name = "TOKEN")]
+    github_token: Option<String>,
+
+    /// Load environment variables for tailwind and trunk from a `.env` file.
+    #[arg(long, global = true, value_name = "PATH")]
+    env_file: Option<PathBuf>,
+
+    /// Set an environment variable for tailwind and trunk; overrides `--env-file`.
+    #[arg(long, global = true, value_name = "KEY=VALUE", value_parser = env_file::parse_assignment)]
+    env: Vec<(String, String)>,
+
+    /// When to use colors, including in tailwind and trunk output passed through.
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Output format for command results.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
     #[command(subcommand)]
     command: Command,
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    /// Human-readable logs.
+    Text,
+    /// A JSON summary on stdout; logs go to stderr.
+    Json,
+}
+
 #[derive(Subcommand, Debug)]
 enum Command {
     /// Initialize Tailwind CSS files in an existing `xilem_web` project.
@@ -46,22 +144,132 @@ enum Command {
         /// Overwrite existing files.
         #[arg(long)]
         force: bool,
+
+        /// Also write a `justfile` with `css`, `css-watch` and `dev` recipes.
+        #[arg(long)]
+        with_justfile: bool,
+
+        /// Directory with house-style `tailwind.css`/`tailwind.config.js` to copy
+        /// instead of the built-in templates.
+        #[arg(long, value_name = "PATH")]
+        template_dir: Option<PathBuf>,
     },
     /// Build Tailwind CSS once.
     Build {
-        /// Disable CSS minification.
+        /// Build every workspace member that has a tailwind input.
         #[arg(long)]
-        no_minify: bool,
+        workspace: bool,
+
+        /// Maximum number of crates to build at once (default: available parallelism).
+        #[arg(long, short = 'j', value_name = "N")]
+        jobs: Option<NonZeroUsize>,
+
+        #[command(flatten)]
+        options: BuildOptions,
     },
     /// Watch inputs and rebuild on changes.
-    Watch,
+    Watch {
+        /// Minify the CSS on every rebuild.
+        #[arg(long)]
+        minify: bool,
+
+        /// Run a one-off build before starting the watcher.
+        #[arg(long, conflicts_with = "no_initial_build")]
+        initial_build: bool,
+
+        /// Skip the initial build and wait for the first change.
+        #[arg(long)]
+        no_initial_build: bool,
+
+        /// Clear the terminal before each rebuild (only on a terminal, in text mode).
+        #[arg(long)]
+        watch_clear: bool,
+    },
+    /// Print the path of the tailwindcss binary, installing it if needed.
+    #[command(alias = "print-binary-path")]
+    Which,
+    /// List the downloaded tailwindcss binaries and their sizes.
+    Cache,
+    /// Delete the downloaded tailwindcss binaries and the generated CSS.
+    Clean {
+        /// Only delete the downloaded binaries and keep the CSS.
+        #[arg(long)]
+        binaries_only: bool,
+
+        /// Also delete the generated CSS of every workspace member.
+        #[arg(long)]
+        workspace: bool,
+    },
+    /// Download the release again and compare it with the installed binary.
+    Verify {
+        /// Replace the installed binary when it does not match.
+        #[arg(long)]
+        fix: bool,
+    },
+    /// Pin a newer tailwind version in `[package.metadata.xilem-web-tailwindcss]`.
+    Upgrade {
+        /// Tag to pin instead of the latest release (e.g. v4.1.5).
+        #[arg(long)]
+        to: Option<String>,
+    },
+    /// Report `tw!` literal classes that match no known Tailwind utility.
+    Lint {
+        #[command(flatten)]
+        options: LintOptions,
+    },
     /// Run Tailwind watch and `trunk serve` together.
     Dev {
+        /// With `--open`, skip the initial build that runs before trunk opens the browser.
+        #[arg(long)]
+        no_prebuild: bool,
+
+        /// Prefix each output line with `[tailwind]` or `[trunk]`.
+        #[arg(long)]
+        prefix_output: bool,
+
         #[command(flatten)]
         trunk: TrunkServeOptions,
     },
 }
 
+#[derive(Args, Debug, Clone)]
+struct LintOptions {
+    /// Source files to scan (default: the `.rs` content files of the input).
+    #[arg(value_name = "GLOB")]
+    globs: Vec<String>,
+
+    /// File of classes that are always valid; also reports the ones never used.
+    #[arg(long, value_name = "PATH")]
+    safelist: Option<PathBuf>,
+
+    /// Report tokens longer than this many characters.
+    #[arg(long, value_name = "CHARS", default_value_t = lint::DEFAULT_MAX_LENGTH)]
+    max_length: usize,
+}
+
+#[derive(Args, Debug, Clone)]
+struct BuildOptions {
+    /// Disable CSS minification.
+    #[arg(long)]
+    no_minify: bool,
+
+    /// Also write Brotli (`.br`) and Gzip (`.gz`) sidecars of the output.
+    #[arg(long)]
+    compress: bool,
+
+    /// Compression level for `--compress` (Brotli 0-11, Gzip 0-9; default: maximum).
+    #[arg(long, requires = "compress")]
+    compress_level: Option<u32>,
+
+    /// Also write a Rust file defining `pub const TAILWIND_CSS: &str` with the CSS.
+    #[arg(long, value_name = "PATH")]
+    emit_rust: Option<PathBuf>,
+
+    /// Also write a JSON map of the theme colors the CSS uses, name to value.
+    #[arg(long, value_name = "PATH")]
+    emit_color_map: Option<PathBuf>,
+}
+
 #[derive(Args, Debug, Clone)]
 #[allow(clippy::struct_excessive_bools)]
 struct TrunkServeOptions {
@@ -112,34 +320,108 @@ struct TrunkServeOptions {
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
-    init_tracing();
+    let css_to_stdout = cli.output.as_deref().is_some_and(output::is_stdout);
+    init_tracing(cli.format, cli.color.enabled(), css_to_stdout);
 
-    if cli.no_downloads {
-        CliSettings::set_prefer_no_downloads(true);
+    apply_settings(&cli)?;
+    check_stdout_output(&cli)?;
+
+    let input_source =
+        InputSource::from_args(cli.input.clone(), cli.input_inline.clone(), cli.stdin)?;
+    let all_members = matches!(
+        cli.command,
+        Command::Build {
+            workspace: true,
+            ..
+        } | Command::Clean {
+            workspace: true,
+            ..
+        }
+    );
+    if let Some(members) = workspace::select(cli.manifest_path.as_deref(), all_members)? {
+        return run_members(&cli, &members, &input_source);
     }
 
-    let manifest_dir = resolve_manifest_dir(cli.manifest_path)?;
+    let manifest_dir = resolve_manifest_dir(cli.manifest_path.clone())?;
+    let _config = configure_processes(&manifest_dir, &cli)?;
 
     match cli.command {
-        Command::Init { force } => init_tailwind(&manifest_dir, force),
-        Command::Build { no_minify } => {
-            let tailwind = resolve_tailwind(&manifest_dir, cli.input.as_ref(), cli.version)?;
-            tailwind.run_once(&manifest_dir, cli.input, cli.output, !no_minify)
+        Command::Init {
+            force,
+            with_justfile,
+            template_dir,
+        } => {
+            init_tailwind(&manifest_dir, force, template_dir.as_deref())?;
+            if with_justfile {
+                init_justfile(&manifest_dir, &input_source, cli.output.as_deref(), force)?;
+            }
+            Ok(())
         }
-        Command::Watch => {
-            let tailwind = resolve_tailwind(&manifest_dir, cli.input.as_ref(), cli.version)?;
-            tailwind.watch(&manifest_dir, cli.input, cli.output)
+        Command::Build { options, .. } => build_project(
+            &manifest_dir,
+            &input_source,
+            cli.output.as_deref(),
+            cli.version,
+            &options,
+            cli.format,
+        ),
+        Command::Watch {
+            minify,
+            initial_build,
+            no_initial_build,
+            watch_clear,
+        } => {
+            let mut input = input::resolve(&manifest_dir, &input_source)?;
+            let tailwind = resolve_tailwind(&manifest_dir, &mut input, cli.version)?;
+            let options = WatchOptions {
+                minify,
+                initial_build: InitialBuild::from_flags(initial_build, no_initial_build),
+                clear: watch_clear
+                    && cli.format == OutputFormat::Text
+                    && std::io::stdout().is_terminal(),
+            };
+            tailwind.watch(&manifest_dir, &input, cli.output.as_deref(), options)
         }
-        Command::Dev { trunk } => {
-            let tailwind = resolve_tailwind(&manifest_dir, cli.input.as_ref(), cli.version)?;
-            run_dev(&manifest_dir, &tailwind, cli.input, cli.output, &trunk)
+        Command::Which => which(&manifest_dir, &input_source, cli.version, cli.format),
+        Command::Cache => cache(cli.format),
+        Command::Clean { binaries_only, .. } => clean(
+            std::slice::from_ref(&manifest_dir),
+            cli.output.as_deref(),
+            binaries_only,
+            cli.format,
+        ),
+        Command::Verify { fix } => {
+            verify(&manifest_dir, &input_source, cli.version, fix, cli.format)
         }
+        Command::Upgrade { to } => upgrade(&manifest_dir, to, cli.format),
+        Command::Lint { options } => lint(&manifest_dir, &input_source, &options, cli.format),
+        Command::Dev {
+            no_prebuild,
+            prefix_output,
+            trunk,
+        } => run_dev(
+            &manifest_dir,
+            &input_source,
+            cli.version,
+            cli.output,
+            &trunk,
+            trunk.open && !no_prebuild,
+            prefix_output.then(CliSettings::color),
+        ),
     }
 }
 
-fn init_tailwind(manifest_dir: &Path, force: bool) -> Result<()> {
+fn init_tailwind(manifest_dir: &Path, force: bool, template_dir: Option<&Path>) -> Result<()> {
     use std::fs;
 
+    if let Some(dir) = template_dir {
+        if !dir.is_dir() {
+            return Err(anyhow!(
+                "template directory {} does not exist",
+                dir.display()
+            ));
+        }
+    }
     let tailwind_css = manifest_dir.join("tailwind.css");
     let tailwind_config = manifest_dir.join("tailwind.config.js");
     let assets_dir = manifest_dir.join("assets");
@@ -148,7 +430,8 @@ fn init_tailwind(manifest_dir: &Path, force: bool) -> Result<()> {
     if tailwind_css.exists() && !force {
         info!("tailwind.css already exists, skipping (use --force to overwrite)");
     } else {
-        fs::write(&tailwind_css, TAILWIND_CSS_TEMPLATE)?;
+        let contents = init_template(template_dir, "tailwind.css", TAILWIND_CSS_TEMPLATE)?;
+        fs::write(&tailwind_css, contents)?;
         info!("Created tailwind.css");
     }
 
@@ -156,7 +439,8 @@ fn init_tailwind(manifest_dir: &Path, force: bool) -> Result<()> {
     if tailwind_config.exists() && !force {
         info!("tailwind.config.js already exists, skipping (use --force to overwrite)");
     } else {
-        fs::write(&tailwind_config, TAILWIND_CONFIG_TEMPLATE)?;
+        let contents = init_template(template_dir, "tailwind.config.js", TAILWIND_CONFIG_TEMPLATE)?;
+        fs::write(&tailwind_config, contents)?;
         info!("Created tailwind.config.js");
     }
 
@@ -189,6 +473,65 @@ fn init_tailwind(manifest_dir: &Path, force: bool) -> Result<()> {
     Ok(())
 }
 
+/// The `name` file from `template_dir` if it has one, else the built-in template.
+fn init_template(template_dir: Option<&Path>, name: &str, builtin: &str) -> Result<String> {
+    let Some(path) = template_dir
+        .map(|dir| dir.join(name))
+        .filter(|path| path.is_file())
+    else {
+        return Ok(builtin.to_string());
+    };
+    debug!("Using {} as the {name} template", path.display());
+    std::fs::read_to_string(&path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Write a `justfile` whose recipes build with the project's resolved input
+/// and output, relative to the manifest dir where possible.
+fn init_justfile(
+    manifest_dir: &Path,
+    input_source: &InputSource,
+    output: Option<&Path>,
+    force: bool,
+) -> Result<()> {
+    let justfile = manifest_dir.join("justfile");
+    if justfile.exists() && !force {
+        info!("justfile already exists, skipping (use --force to overwrite)");
+        return Ok(());
+    }
+    if matches!(input_source, InputSource::Inline(_)) {
+        return Err(anyhow!(
+            "--with-justfile needs an input file; --input-inline and --stdin have none"
+        ));
+    }
+    if output.is_some_and(output::is_stdout) {
+        return Err(anyhow!("--with-justfile needs an output file, not `-o -`"));
+    }
+
+    let input = input::resolve(manifest_dir, input_source)?;
+    let output = tailwind::output_path(manifest_dir, output.map(Path::to_path_buf));
+    let contents = JUSTFILE_TEMPLATE
+        .replace("@INPUT@", &just_path(manifest_dir, input.source())?)
+        .replace("@OUTPUT@", &just_path(manifest_dir, &output)?);
+    std::fs::write(&justfile, contents)?;
+    info!("Created justfile (run `just css`, `just css-watch` or `just dev`)");
+    Ok(())
+}
+
+/// `path` relative to `manifest_dir` when it is inside it, for a single-quoted
+/// just string. Those have no escapes, so Windows backslashes are kept as they
+/// are, but they cannot hold a `'`.
+fn just_path(manifest_dir: &Path, path: &Path) -> Result<String> {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let path = path.strip_prefix(manifest_dir).unwrap_or(&path);
+    let path = path.to_string_lossy();
+    if path.contains('\'') {
+        return Err(anyhow!(
+            "cannot write {path} to a justfile: it contains a `'`"
+        ));
+    }
+    Ok(path.into_owned())
+}
+
 const TAILWIND_CSS_TEMPLATE: &str = r#"@import "tailwindcss";
 "#;
 
@@ -201,24 +544,617 @@ module.exports = {
 };
 "#;
 
+const JUSTFILE_TEMPLATE: &str = r"# Tailwind CSS tasks generated by `xilem-web-tailwindcss init`.
+
+input := '@INPUT@'
+output := '@OUTPUT@'
+
+# Build Tailwind CSS once.
+css:
+    xilem-web-tailwindcss --input '{{input}}' --output '{{output}}' build
+
+# Rebuild Tailwind CSS on changes.
+css-watch:
+    xilem-web-tailwindcss --input '{{input}}' --output '{{output}}' watch
+
+# Run Tailwind watch and trunk serve together.
+dev:
+    xilem-web-tailwindcss --input '{{input}}' --output '{{output}}' dev
+";
+
+/// The requested tailwind, installed and checked against `tailwind.lock`.
+///
+/// A `--tailwind-config` or `--config-inline` config is passed with
+/// `--config` to tailwind v3, and loaded from a stylesheet wrapping `input`
+/// otherwise, since v4 has no `--config` flag.
 fn resolve_tailwind(
     manifest_dir: &Path,
-    input_path: Option<&PathBuf>,
+    input: &mut input::ResolvedInput,
     version: Option<String>,
 ) -> Result<TailwindCli> {
-    if let Some(version) = version {
-        let version = match version.as_str() {
-            "v4" | "4" | "latest" => TailwindCli::LATEST_TAG.to_string(),
-            _ => version,
-        };
+    let tailwind =
+        requested_tailwind(manifest_dir, input.path(), version)?.ensure_locked(manifest_dir)?;
+    compat::check_duplicate_entries(input.source(), CliSettings::fix_input())?;
+    let major = match tailwind.major_version() {
+        Ok(Some(major)) => {
+            compat::warn_on_mismatch(major, input.source());
+            Some(major)
+        }
+        Ok(None) => {
+            debug!("Could not detect the tailwindcss major version");
+            None
+        }
+        Err(err) => {
+            debug!("Skipping tailwind compatibility check: {err:#}");
+            None
+        }
+    };
+    let Some(config) = CliSettings::tailwind_config() else {
+        return Ok(tailwind);
+    };
+    if major == Some(3) && !input::is_css(config) {
+        return Ok(tailwind.with_config_flag(config.to_path_buf()));
+    }
+    input.import_config(config)?;
+    Ok(tailwind)
+}
+
+fn requested_tailwind(
+    manifest_dir: &Path,
+    input_path: &Path,
+    version: Option<String>,
+) -> Result<TailwindCli> {
+    if let Some(version) = requested_version(manifest_dir, version)? {
         return Ok(TailwindCli::new(version));
     }
 
-    TailwindCli::autodetect(manifest_dir, input_path).ok_or_else(|| {
+    TailwindCli::autodetect(input_path).ok_or_else(|| {
         anyhow!("unable to detect tailwind input; expected tailwind.css or --input. Run 'xilem-web-tailwindcss init' first.")
     })
 }
 
+/// `--version`, or the version pinned in Cargo.toml when none was given.
+fn requested_version(manifest_dir: &Path, version: Option<String>) -> Result<Option<String>> {
+    let version = match version {
+        Some(version) => Some(version),
+        None => metadata::pinned_version(manifest_dir)?,
+    };
+    Ok(version.map(|version| match version.as_str() {
+        "v4" | "4" | "latest" => TailwindCli::LATEST_TAG.to_string(),
+        _ => version,
+    }))
+}
+
+/// The requested tailwind for commands that only need the binary.
+fn binary_tailwind(
+    manifest_dir: &Path,
+    input_source: &InputSource,
+    version: Option<String>,
+) -> Result<TailwindCli> {
+    if let Some(version) = requested_version(manifest_dir, version)? {
+        return Ok(TailwindCli::new(version));
+    }
+    let input = input::resolve(manifest_dir, input_source)?;
+    // The binary does not depend on an input file, so fall back to the
+    // latest release when none is found.
+    Ok(TailwindCli::autodetect(input.path()).unwrap_or_else(TailwindCli::latest))
+}
+
+/// Print the managed binary path, installing it first if needed.
+fn which(
+    manifest_dir: &Path,
+    input_source: &InputSource,
+    version: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    // A query never creates or rewrites tailwind.lock.
+    let tailwind = binary_tailwind(manifest_dir, input_source, version)?.locked(manifest_dir)?;
+    tailwind.ensure_installed()?;
+    let path = tailwind.get_binary_path()?;
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::json!({ "path": path }));
+    } else {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn cache(format: OutputFormat) -> Result<()> {
+    let (install_dir, binaries) = TailwindCli::cached_binaries()?;
+    let total: u64 = binaries.iter().map(|binary| binary.size).sum();
+    if format == OutputFormat::Json {
+        let binaries: Vec<_> = binaries
+            .iter()
+            .map(|binary| {
+                serde_json::json!({
+                    "path": binary.path,
+                    "version": binary.version,
+                    "target": binary.target,
+                    "bytes": binary.size,
+                })
+            })
+            .collect();
+        let summary = serde_json::json!({
+            "dir": install_dir,
+            "binaries": binaries,
+            "total_bytes": total,
+        });
+        println!("{summary}");
+        return Ok(());
+    }
+    println!("{}", install_dir.display());
+    for binary in &binaries {
+        println!(
+            "  {:<16} {:<28} {:>10}",
+            binary.version,
+            binary.target,
+            format_size(binary.size)
+        );
+    }
+    println!(
+        "{} cached binaries, {} total",
+        binaries.len(),
+        format_size(total)
+    );
+    Ok(())
+}
+
+/// Empty the binary cache and, unless `binaries_only`, delete the generated
+/// CSS of each crate in `manifest_dirs`. `-o -` has no file to delete.
+fn clean(
+    manifest_dirs: &[PathBuf],
+    output_path: Option<&Path>,
+    binaries_only: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let (install_dir, mut removed) = TailwindCli::clear_cache()?;
+    if !binaries_only && !output_path.is_some_and(output::is_stdout) {
+        for manifest_dir in manifest_dirs {
+            let css = tailwind::output_path(manifest_dir, output_path.map(Path::to_path_buf));
+            match std::fs::remove_file(&css) {
+                Ok(()) => removed.push(css),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    return Err(err).with_context(|| format!("failed to remove {}", css.display()));
+                }
+            }
+        }
+    }
+    if format == OutputFormat::Json {
+        let summary = serde_json::json!({
+            "dir": install_dir,
+            "removed": removed,
+        });
+        println!("{summary}");
+        return Ok(());
+    }
+    for path in &removed {
+        println!("removed {}", path.display());
+    }
+    if removed.is_empty() {
+        println!("nothing to clean");
+    }
+    Ok(())
+}
+
+/// A byte count in MiB with one decimal, e.g. `112.3 MiB`.
+fn format_size(bytes: u64) -> String {
+    let tenths = bytes * 10 / (1024 * 1024);
+    format!("{}.{} MiB", tenths / 10, tenths % 10)
+}
+
+/// Build a single crate and print its summary.
+fn build_project(
+    manifest_dir: &Path,
+    input_source: &InputSource,
+    output_path: Option<&Path>,
+    version: Option<String>,
+    options: &BuildOptions,
+    format: OutputFormat,
+) -> Result<()> {
+    let mut input = input::resolve(manifest_dir, input_source)?;
+    let tailwind = resolve_tailwind(manifest_dir, &mut input, version)?;
+    if output_path.is_some_and(output::is_stdout) {
+        let temp_path = output::stdout_temp_path();
+        build(manifest_dir, &tailwind, &input, Some(&temp_path), options)?;
+        return output::write_to_stdout(&temp_path);
+    }
+    let build = build(manifest_dir, &tailwind, &input, output_path, options)?;
+    if format == OutputFormat::Json {
+        let summary = serde_json::json!({
+            "output": build.output_path,
+            "duration_ms": build.duration.as_millis(),
+            "diagnostics": build.diagnostics.iter().map(Diagnostic::to_json).collect::<Vec<_>>(),
+        });
+        println!("{summary}");
+    }
+    Ok(())
+}
+
+fn build(
+    manifest_dir: &Path,
+    tailwind: &TailwindCli,
+    input: &input::ResolvedInput,
+    output_path: Option<&Path>,
+    options: &BuildOptions,
+) -> Result<tailwind::BuildOutcome> {
+    components::generate(manifest_dir, input)?;
+    let build = tailwind.run_once(
+        manifest_dir,
+        input.path(),
+        output_path.map(Path::to_path_buf),
+        !options.no_minify,
+    )?;
+    if options.compress {
+        compress::write_sidecars(&build.output_path, options.compress_level)?;
+    } else {
+        compress::warn_if_stale_sidecars(&build.output_path);
+    }
+    if let Some(rust_path) = &options.emit_rust {
+        embed::write_rust_module(&build.output_path, &manifest_dir.join(rust_path))?;
+    }
+    if let Some(map_path) = &options.emit_color_map {
+        color_map::write_color_map(&build.output_path, &manifest_dir.join(map_path))?;
+    }
+    Ok(build)
+}
+
+/// Run a command that was given several crates, through `--workspace` or a
+/// `--manifest-path` glob.
+fn run_members(cli: &Cli, members: &[PathBuf], input_source: &InputSource) -> Result<()> {
+    if let Command::Clean { binaries_only, .. } = cli.command {
+        return clean(members, cli.output.as_deref(), binaries_only, cli.format);
+    }
+    let Command::Build { options, jobs, .. } = &cli.command else {
+        return Err(anyhow!(
+            "only `build` and `clean` accept a --manifest-path glob"
+        ));
+    };
+    let _config = configure_processes(&env::current_dir()?, cli)?;
+    build_members(
+        members,
+        input_source,
+        cli.output.as_deref(),
+        cli.version.as_deref(),
+        options,
+        jobs.or_else(|| thread::available_parallelism().ok()),
+        cli.format,
+    )
+}
+
+/// Build each crate that has a tailwind input, at most `jobs` at a time,
+/// then report a per-crate summary. Fails if any build failed.
+fn build_members(
+    members: &[PathBuf],
+    input_source: &InputSource,
+    output_path: Option<&Path>,
+    version: Option<&str>,
+    options: &BuildOptions,
+    jobs: Option<NonZeroUsize>,
+    format: OutputFormat,
+) -> Result<()> {
+    let jobs = jobs.map_or(1, NonZeroUsize::get).min(members.len());
+    let next = AtomicUsize::new(0);
+    let mut results = thread::scope(|scope| {
+        let workers = (0..jobs)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(member) = members.get(index) else {
+                            break done;
+                        };
+                        let result =
+                            build_member(member, input_source, output_path, version, options);
+                        done.push((index, result));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("build worker panicked"))
+            .collect::<Vec<_>>()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    let results = results
+        .into_iter()
+        .map(|(_, result)| result)
+        .collect::<Vec<_>>();
+    let failed = results
+        .iter()
+        .filter(|result| result["status"] == "failed")
+        .count();
+
+    let total = results.len();
+    if format == OutputFormat::Json {
+        println!("{}", serde_json::Value::Array(results));
+    } else {
+        info!("Summary:");
+        for result in &results {
+            let manifest = result["manifest"].as_str().unwrap_or_default();
+            match result["status"].as_str() {
+                Some("ok") => info!("  ok      {manifest} ({}ms)", result["duration_ms"]),
+                Some("failed") => info!("  failed  {manifest}"),
+                _ => info!("  skipped {manifest}"),
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(anyhow!("{failed} of {total} builds failed"));
+    }
+    Ok(())
+}
+
+/// Build one crate and describe the outcome for the summary.
+fn build_member(
+    member: &Path,
+    input_source: &InputSource,
+    output_path: Option<&Path>,
+    version: Option<&str>,
+    options: &BuildOptions,
+) -> serde_json::Value {
+    let outcome = input::resolve(member, input_source).and_then(|mut input| {
+        if !input.path().exists() {
+            return Ok(None);
+        }
+        let tailwind = resolve_tailwind(member, &mut input, version.map(str::to_string))?;
+        build(member, &tailwind, &input, output_path, options).map(Some)
+    });
+    match outcome {
+        Ok(Some(build)) => serde_json::json!({
+            "manifest": member,
+            "status": "ok",
+            "output": build.output_path,
+            "duration_ms": build.duration.as_millis(),
+            "diagnostics": build.diagnostics.iter().map(Diagnostic::to_json).collect::<Vec<_>>(),
+        }),
+        Ok(None) => {
+            info!("Skipping {}: no tailwind input", member.display());
+            serde_json::json!({ "manifest": member, "status": "skipped" })
+        }
+        Err(err) => {
+            warn!("Build failed for {}: {err:#}", member.display());
+            serde_json::json!({
+                "manifest": member,
+                "status": "failed",
+                "error": format!("{err:#}"),
+            })
+        }
+    }
+}
+
+/// `-o -` only makes sense for a single `build` whose stdout carries nothing
+/// but the CSS.
+fn check_stdout_output(cli: &Cli) -> Result<()> {
+    if !cli.output.as_deref().is_some_and(output::is_stdout) {
+        return Ok(());
+    }
+    let Command::Build {
+        options, workspace, ..
+    } = &cli.command
+    else {
+        return Err(anyhow!("only `build` can write the CSS to stdout (`-o -`)"));
+    };
+    if *workspace || cli.manifest_path.as_deref().is_some_and(workspace::is_glob) {
+        return Err(anyhow!(
+            "`-o -` cannot be combined with a multi-crate build"
+        ));
+    }
+    if cli.format == OutputFormat::Json {
+        return Err(anyhow!("`-o -` cannot be combined with `--format json`"));
+    }
+    if options.compress {
+        return Err(anyhow!("`--compress` needs an output file, not `-o -`"));
+    }
+    Ok(())
+}
+
+/// Global flags that configure how tailwind is installed.
+fn apply_settings(cli: &Cli) -> Result<()> {
+    if cli.no_downloads {
+        CliSettings::set_prefer_no_downloads(true);
+    }
+    CliSettings::set_frozen(cli.frozen);
+    CliSettings::set_no_verify(cli.no_verify);
+    CliSettings::set_fix_input(cli.fix_input);
+    CliSettings::set_color(cli.color.enabled());
+    if let Some(repo) = &cli.release_repo {
+        CliSettings::set_release_repo(repo.clone())?;
+    }
+    if let Some(token) = &cli.github_token {
+        CliSettings::set_github_token(token.clone());
+    }
+    Ok(())
+}
+
+/// The environment and tailwind config for spawned processes.
+///
+/// Keep the result alive while tailwind runs: it owns the temporary file
+/// written for `--config-inline`.
+fn configure_processes(dir: &Path, cli: &Cli) -> Result<Option<input::ResolvedInput>> {
+    configure_child_env(dir, cli.env_file.as_deref(), cli.env.clone())?;
+    let config = input::resolve_config(
+        dir,
+        cli.tailwind_config.as_deref(),
+        cli.config_inline.as_deref(),
+    )?;
+    if let Some(config) = &config {
+        CliSettings::set_tailwind_config(config.path().to_path_buf());
+    }
+    Ok(config)
+}
+
+/// Variables from `--env-file`, overridden by explicit `--env` values, for
+/// every spawned tailwind and trunk process.
+fn configure_child_env(
+    manifest_dir: &Path,
+    env_file: Option<&Path>,
+    explicit: Vec<(String, String)>,
+) -> Result<()> {
+    let mut child_env = match env_file {
+        Some(path) => env_file::load(&manifest_dir.join(path))?,
+        None => Vec::new(),
+    };
+    child_env.retain(|(key, _)| !explicit.iter().any(|(explicit, _)| explicit == key));
+    child_env.extend(explicit);
+    CliSettings::set_child_env(child_env);
+    Ok(())
+}
+
+/// Scan `tw!` literals and fail when any class matches no known utility.
+fn lint(
+    manifest_dir: &Path,
+    input_source: &InputSource,
+    options: &LintOptions,
+    format: OutputFormat,
+) -> Result<()> {
+    let max_length = options.max_length;
+    let files = if options.globs.is_empty() {
+        let input = input::resolve(manifest_dir, input_source)?;
+        sources::content_files(manifest_dir, input.path())
+    } else {
+        lint::expand(manifest_dir, &options.globs)?
+    };
+    let report = lint::run(&files, options.safelist.as_deref(), max_length)?;
+    if format == OutputFormat::Json {
+        let to_json = |found: &lint::FoundClass| {
+            serde_json::json!({
+                "path": found.path,
+                "line": found.line,
+                "class": found.class,
+            })
+        };
+        let summary = serde_json::json!({
+            "files": report.files,
+            "unknown": report.unknown.iter().map(to_json).collect::<Vec<_>>(),
+            "too_long": report.too_long.iter().map(to_json).collect::<Vec<_>>(),
+            "unreferenced": report.unreferenced,
+        });
+        println!("{summary}");
+    } else {
+        for found in &report.unknown {
+            warn!(
+                "{}:{}: unknown class `{}`",
+                found.path.display(),
+                found.line,
+                found.class
+            );
+        }
+        for found in &report.too_long {
+            warn!(
+                "{}:{}: class `{}` is {} characters long; is it a pasted URL or attribute?",
+                found.path.display(),
+                found.line,
+                found.class,
+                found.class.chars().count()
+            );
+        }
+        for class in &report.unreferenced {
+            warn!("Safelisted class `{class}` is never used");
+        }
+        info!("Scanned {} files", report.files);
+    }
+    if !report.too_long.is_empty() {
+        return Err(anyhow!(
+            "found {} classes over {max_length} characters; raise the limit with --max-length or a `// tw-lint: max-length = N` comment",
+            report.too_long.len()
+        ));
+    }
+    if report.unknown.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "found {} unknown classes; fix the typos or add them to a --safelist",
+            report.unknown.len()
+        ))
+    }
+}
+
+/// Compare the installed binary with a fresh download of its release.
+fn verify(
+    manifest_dir: &Path,
+    input_source: &InputSource,
+    version: Option<String>,
+    fix: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let verification =
+        binary_tailwind(manifest_dir, input_source, version)?.verify(manifest_dir, fix)?;
+    let status = match (verification.matches(), verification.fixed) {
+        (true, _) => "match",
+        (false, true) => "fixed",
+        (false, false) => "mismatch",
+    };
+    if format == OutputFormat::Json {
+        let summary = serde_json::json!({
+            "version": verification.version,
+            "path": verification.binary_path,
+            "installed_sha256": verification.installed,
+            "downloaded_sha256": verification.downloaded,
+            "locked_sha256": verification.locked,
+            "status": status,
+        });
+        println!("{summary}");
+    } else {
+        info!("tailwindcss@{}", verification.version);
+        info!("  installed  {}", verification.installed);
+        info!("  downloaded {}", verification.downloaded);
+        if let Some(locked) = &verification.locked {
+            info!("  locked     {locked}");
+        }
+    }
+    if let Some(locked) = &verification.locked {
+        if *locked != verification.downloaded {
+            warn!(
+                "The downloaded release does not match tailwind.lock; the release may have been replaced upstream"
+            );
+        }
+    }
+    match status {
+        "match" => info!("Installed binary matches the release"),
+        "fixed" => info!(
+            "Replaced {} with the downloaded release",
+            verification.binary_path.display()
+        ),
+        _ => {
+            return Err(anyhow!(
+                "installed tailwindcss@{} does not match the release; rerun with --fix to replace it",
+                verification.version
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn upgrade(manifest_dir: &Path, to: Option<String>, format: OutputFormat) -> Result<()> {
+    let tag = match to {
+        Some(tag) if tag.starts_with(|c: char| c.is_ascii_digit()) => format!("v{tag}"),
+        Some(tag) => tag,
+        None => TailwindCli::latest_release_tag()?,
+    };
+    // Download before pinning so a bad tag never ends up in Cargo.toml.
+    let tailwind = TailwindCli::new(tag).ensure_locked(manifest_dir)?;
+    let previous = metadata::pin_version(manifest_dir, tailwind.version())?;
+
+    match previous.as_deref() {
+        Some(previous) if previous == tailwind.version() => {
+            info!("tailwindcss is already pinned to {previous}");
+        }
+        Some(previous) => info!("Upgraded tailwindcss {previous} -> {}", tailwind.version()),
+        None => info!("Pinned tailwindcss {}", tailwind.version()),
+    }
+    if format == OutputFormat::Json {
+        let summary = serde_json::json!({
+            "previous": previous,
+            "version": tailwind.version(),
+        });
+        println!("{summary}");
+    }
+    Ok(())
+}
+
 fn resolve_manifest_dir(manifest_path: Option<PathBuf>) -> Result<PathBuf> {
     let path = manifest_path.unwrap_or_else(|| PathBuf::from("."));
     let dir = if path.is_dir() {
@@ -234,29 +1170,81 @@ fn resolve_manifest_dir(manifest_path: Option<PathBuf>) -> Result<PathBuf> {
 
 fn run_dev(
     manifest_dir: &Path,
-    tailwind: &TailwindCli,
-    input_path: Option<PathBuf>,
+    input_source: &InputSource,
+    version: Option<String>,
     output_path: Option<PathBuf>,
     trunk: &TrunkServeOptions,
+    prebuild: bool,
+    prefix_color: Option<bool>,
 ) -> Result<()> {
+    // Ctrl-C reaches tailwind and trunk too; the wait below then stops both
+    // and returns, so the temporary inputs are removed on the way out.
+    tailwind::handle_interrupts();
+    let mut input = input::resolve(manifest_dir, input_source)?;
+    let tailwind = resolve_tailwind(manifest_dir, &mut input, version)?;
+    components::generate(manifest_dir, &input)?;
+    let input_path = input.path();
+
     info!("Starting Tailwind watch and trunk serve...");
     tailwind.ensure_installed()?;
 
+    // Build once up front so the browser opened by trunk never sees the page
+    // without styles.
+    if prebuild {
+        tailwind.run_once(manifest_dir, input_path, output_path.clone(), false)?;
+    }
+
     let mut tailwind_child = tailwind.run_with_stdio(
         manifest_dir,
         input_path,
         output_path,
         true,
         false,
-        Stdio::inherit(),
-        Stdio::inherit(),
+        child_stdio(prefix_color),
+        child_stdio(prefix_color),
     )?;
-    let mut trunk_child = spawn_trunk(manifest_dir, trunk)?;
+    let mut trunk_child = spawn_trunk(manifest_dir, trunk, prefix_color)?;
 
-    wait_for_dev_exit(&mut tailwind_child, &mut trunk_child)
+    // With `--prefix-output`, the children write into pipes that are copied
+    // to our own stdout/stderr with a tag. The forwarding threads end once the
+    // children exit, so they are left detached.
+    if let Some(color) = prefix_color {
+        forward_output(&mut tailwind_child, Tag::tailwind(color));
+    }
+
+    // A restart should not open another browser tab.
+    let restart_options = TrunkServeOptions {
+        open: false,
+        ..trunk.clone()
+    };
+    let config_path = manifest_dir.join(trunk.config.as_deref().unwrap_or(Path::new("Trunk.toml")));
+    wait_for_dev_exit(&mut tailwind_child, &mut trunk_child, &config_path, || {
+        spawn_trunk(manifest_dir, &restart_options, prefix_color)
+    })
+}
+
+fn forward_output(child: &mut Child, tag: Tag) {
+    if let Some(stdout) = child.stdout.take() {
+        tag.forward(stdout, std::io::stdout());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        tag.forward(stderr, std::io::stderr());
+    }
+}
+
+fn child_stdio(prefix_color: Option<bool>) -> Stdio {
+    if prefix_color.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::inherit()
+    }
 }
 
-fn spawn_trunk(manifest_dir: &Path, trunk: &TrunkServeOptions) -> Result<Child> {
+fn spawn_trunk(
+    manifest_dir: &Path,
+    trunk: &TrunkServeOptions,
+    prefix_color: Option<bool>,
+) -> Result<Child> {
     let mut cmd = ProcessCommand::new("trunk");
     cmd.arg("serve");
 
@@ -294,6 +1282,8 @@ fn spawn_trunk(manifest_dir: &Path, trunk: &TrunkServeOptions) -> Result<Child>
         cmd.arg("--public-url").arg(public_url);
     }
 
+    cmd.envs(CliSettings::child_env().iter().map(|(k, v)| (k, v)));
+
     if let Some(value) = env::var_os("NO_COLOR") {
         match value.to_string_lossy().as_ref() {
             "1" => {
@@ -306,18 +1296,36 @@ fn spawn_trunk(manifest_dir: &Path, trunk: &TrunkServeOptions) -> Result<Child>
         }
     }
 
-    let child = cmd
+    let mut child = cmd
         .current_dir(manifest_dir)
         .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
+        .stdout(child_stdio(prefix_color))
+        .stderr(child_stdio(prefix_color))
         .spawn()
         .context("failed to spawn trunk serve")?;
+    if let Some(color) = prefix_color {
+        forward_output(&mut child, Tag::trunk(color));
+    }
     Ok(child)
 }
 
-fn wait_for_dev_exit(tailwind: &mut Child, trunk: &mut Child) -> Result<()> {
+/// Wait until either child exits or Ctrl-C is pressed, restarting trunk
+/// whenever its config file changes; trunk does not reliably reload
+/// `Trunk.toml` by itself.
+fn wait_for_dev_exit(
+    tailwind: &mut Child,
+    trunk: &mut Child,
+    trunk_config: &Path,
+    mut respawn_trunk: impl FnMut() -> Result<Child>,
+) -> Result<()> {
+    let mut config_modified = modified_time(trunk_config);
     loop {
+        if tailwind::interrupted() {
+            terminate_child("tailwindcss watch", tailwind);
+            terminate_child("trunk", trunk);
+            return Ok(());
+        }
+
         if let Some(status) = tailwind.try_wait()? {
             terminate_child("trunk", trunk);
             return exit_status("tailwindcss watch", status);
@@ -328,10 +1336,30 @@ fn wait_for_dev_exit(tailwind: &mut Child, trunk: &mut Child) -> Result<()> {
             return exit_status("trunk serve", status);
         }
 
+        let modified = modified_time(trunk_config);
+        if modified != config_modified {
+            config_modified = modified;
+            info!("{} changed; restarting trunk serve", trunk_config.display());
+            terminate_child("trunk", trunk);
+            *trunk = match respawn_trunk() {
+                Ok(child) => child,
+                Err(err) => {
+                    terminate_child("tailwindcss watch", tailwind);
+                    return Err(err);
+                }
+            };
+        }
+
         std::thread::sleep(Duration::from_millis(200));
     }
 }
 
+fn modified_time(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+}
+
 fn terminate_child(label: &str, child: &mut Child) {
     if let Err(err) = child.kill() {
         warn!(error = %err, "Failed to terminate {label} process");
@@ -342,16 +1370,25 @@ fn terminate_child(label: &str, child: &mut Child) {
     }
 }
 
+/// A child stopped by the same Ctrl-C that stopped us is not a failure.
 fn exit_status(label: &str, status: std::process::ExitStatus) -> Result<()> {
-    if status.success() {
+    if status.success() || tailwind::interrupted() {
         Ok(())
     } else {
         Err(anyhow!("{label} exited with status {status}"))
     }
 }
 
-fn init_tracing() {
+/// Logs go to stderr when stdout carries JSON or the CSS itself.
+fn init_tracing(format: OutputFormat, color: bool, css_to_stdout: bool) {
     let filter = env::var("RUST_LOG").unwrap_or_else(|_| "xilem_web_tailwindcss=info".to_string());
     let filter = EnvFilter::try_new(filter).unwrap_or_else(|_| EnvFilter::new("info"));
-    tracing_subscriber::fmt().with_env_filter(filter).init();
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_ansi(color);
+    if format == OutputFormat::Json || css_to_stdout {
+        subscriber.with_writer(std::io::stderr).init();
+    } else {
+        subscriber.init();
+    }
 }
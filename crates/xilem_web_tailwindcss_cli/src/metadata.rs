@@ -0,0 +1,95 @@
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::Path;
+use toml_edit::{DocumentMut, Item, Table, value};
+
+/// Key under `[package.metadata]` holding this tool's settings.
+const METADATA_KEY: &str = "xilem-web-tailwindcss";
+
+/// The tailwind version pinned in `[package.metadata.xilem-web-tailwindcss]`.
+pub fn pinned_version(manifest_dir: &Path) -> Result<Option<String>> {
+    let Some(doc) = read_manifest(manifest_dir)? else {
+        return Ok(None);
+    };
+    Ok(doc
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get(METADATA_KEY))
+        .and_then(|settings| settings.get("version"))
+        .and_then(Item::as_str)
+        .map(str::to_string))
+}
+
+/// The content roots listed in `[package.metadata.xilem-web-tailwindcss]
+/// sources`, relative to the manifest directory.
+pub fn sources(manifest_dir: &Path) -> Result<Vec<String>> {
+    let Some(doc) = read_manifest(manifest_dir)? else {
+        return Ok(Vec::new());
+    };
+    let Some(sources) = doc
+        .get("package")
+        .and_then(|package| package.get("metadata"))
+        .and_then(|metadata| metadata.get(METADATA_KEY))
+        .and_then(|settings| settings.get("sources"))
+    else {
+        return Ok(Vec::new());
+    };
+    sources
+        .as_array()
+        .and_then(|sources| {
+            sources
+                .iter()
+                .map(|source| source.as_str().map(str::to_string))
+                .collect()
+        })
+        .ok_or_else(|| anyhow!("package.metadata.{METADATA_KEY}.sources must be an array of paths"))
+}
+
+/// Pin `version` in `Cargo.toml`, keeping the rest of the file untouched.
+/// Returns the previously pinned version.
+pub fn pin_version(manifest_dir: &Path, version: &str) -> Result<Option<String>> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    let mut doc = read_manifest(manifest_dir)?
+        .ok_or_else(|| anyhow!("no Cargo.toml in {}", manifest_dir.display()))?;
+    let package = doc
+        .get_mut("package")
+        .and_then(Item::as_table_mut)
+        .ok_or_else(|| anyhow!("{} has no [package] table", manifest_path.display()))?;
+    let metadata = implicit_table(package, "metadata")?;
+    let settings = metadata
+        .entry(METADATA_KEY)
+        .or_insert_with(|| Item::Table(Table::new()))
+        .as_table_like_mut()
+        .ok_or_else(|| anyhow!("package.metadata.{METADATA_KEY} is not a table"))?;
+    let previous = settings
+        .get("version")
+        .and_then(Item::as_str)
+        .map(str::to_string);
+    settings.insert("version", value(version));
+    fs::write(&manifest_path, doc.to_string())
+        .with_context(|| format!("failed to write {}", manifest_path.display()))?;
+    Ok(previous)
+}
+
+fn implicit_table<'a>(parent: &'a mut Table, key: &str) -> Result<&'a mut Table> {
+    parent
+        .entry(key)
+        .or_insert_with(|| {
+            let mut table = Table::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| anyhow!("package.{key} is not a table"))
+}
+
+fn read_manifest(manifest_dir: &Path) -> Result<Option<DocumentMut>> {
+    let manifest_path = manifest_dir.join("Cargo.toml");
+    let Ok(contents) = fs::read_to_string(&manifest_path) else {
+        return Ok(None);
+    };
+    contents
+        .parse()
+        .map(Some)
+        .with_context(|| format!("failed to parse {}", manifest_path.display()))
+}
@@ -0,0 +1,97 @@
+use anyhow::{Context, Result};
+use std::env;
+use std::fs;
+use std::io::{self, Write as _};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::install;
+
+/// Where a one-off build writes before the result is moved into place.
+pub fn temp_path(output_path: &Path) -> PathBuf {
+    install::with_suffix(output_path, &format!("{}.tmp", std::process::id()))
+}
+
+/// Whether `path` is `-`, i.e. the CSS goes to stdout.
+pub fn is_stdout(path: &Path) -> bool {
+    path == Path::new("-")
+}
+
+/// Where a build for `-o -` writes before the CSS is copied to stdout.
+pub fn stdout_temp_path() -> PathBuf {
+    env::temp_dir().join(format!("xilem-web-tailwindcss-{}.css", std::process::id()))
+}
+
+/// Copy a finished build to stdout and remove the file.
+///
+/// The whole input was read before tailwind started, so writing here cannot
+/// block on a pipe that is still feeding us.
+pub fn write_to_stdout(path: &Path) -> Result<()> {
+    let css = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let _ = fs::remove_file(path);
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(&css)
+        .and_then(|()| stdout.flush())
+        .context("failed to write the CSS to stdout")
+}
+
+/// Atomically replace `output_path` with `temp_path`, so readers never see a
+/// half-written stylesheet.
+///
+/// On Unix the previous file's mode and owner are carried over, since deploys
+/// may depend on them. A new output keeps the directory's defaults.
+pub fn replace(temp_path: &Path, output_path: &Path) -> Result<()> {
+    if let Ok(previous) = fs::metadata(output_path) {
+        preserve_metadata(&previous, temp_path)?;
+    }
+    fs::rename(temp_path, output_path)
+        .with_context(|| format!("failed to move CSS into {}", output_path.display()))
+}
+
+#[cfg(unix)]
+fn preserve_metadata(previous: &fs::Metadata, temp_path: &Path) -> Result<()> {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    fs::set_permissions(temp_path, fs::Permissions::from_mode(previous.mode()))
+        .with_context(|| format!("failed to set permissions on {}", temp_path.display()))?;
+    // Changing the owner needs privileges; the group often works without them.
+    if let Err(err) =
+        std::os::unix::fs::chown(temp_path, Some(previous.uid()), Some(previous.gid()))
+    {
+        debug!("Could not preserve the output's owner: {err}");
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn preserve_metadata(previous: &fs::Metadata, temp_path: &Path) -> Result<()> {
+    fs::set_permissions(temp_path, previous.permissions())
+        .with_context(|| format!("failed to set permissions on {}", temp_path.display()))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::{replace, temp_path};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn replacing_keeps_the_previous_mode() {
+        let dir = std::env::temp_dir().join(format!("xilem-tw-output-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("tailwind.css");
+        fs::write(&output, "old").unwrap();
+        fs::set_permissions(&output, fs::Permissions::from_mode(0o640)).unwrap();
+
+        let temp = temp_path(&output);
+        fs::write(&temp, "new").unwrap();
+        replace(&temp, &output).unwrap();
+
+        assert_eq!(fs::read_to_string(&output).unwrap(), "new");
+        let mode = fs::metadata(&output).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+        assert!(!temp.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
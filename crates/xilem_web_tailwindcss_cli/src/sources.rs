@@ -0,0 +1,235 @@
+use glob::{MatchOptions, Pattern};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// A content path or glob that tailwind scans for class names.
+#[derive(Debug, Clone)]
+pub struct ContentSource {
+    pattern: PathBuf,
+}
+
+impl ContentSource {
+    pub fn new(base: &Path, raw: &str) -> Self {
+        Self {
+            pattern: normalize(&base.join(raw)),
+        }
+    }
+
+    pub fn pattern(&self) -> &Path {
+        &self.pattern
+    }
+
+    /// The deepest directory (or file) that contains every match.
+    pub fn root(&self) -> PathBuf {
+        self.pattern
+            .components()
+            .take_while(|component| !is_glob(&component.as_os_str().to_string_lossy()))
+            .collect()
+    }
+
+    /// Whether `path` is covered by this source, either by matching the glob
+    /// or by living under a plain directory source.
+    pub fn matches(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        let pattern = self.pattern.to_string_lossy();
+        if !is_glob(&pattern) {
+            return path.starts_with(&self.pattern);
+        }
+        let Ok(glob) = Pattern::new(&pattern) else {
+            return false;
+        };
+        glob.matches_path_with(
+            &path,
+            MatchOptions {
+                case_sensitive: true,
+                require_literal_separator: true,
+                require_literal_leading_dot: false,
+            },
+        )
+    }
+}
+
+/// Collect content sources declared by `@source` directives in the input CSS
+/// and by the `content` array of `tailwind.config.js`.
+pub fn discover(manifest_dir: &Path, input_path: &Path) -> Vec<ContentSource> {
+    let mut sources = Vec::new();
+
+    for (path, css) in stylesheets(input_path) {
+        let base = path.parent().unwrap_or(manifest_dir);
+        for raw in source_directives(&css) {
+            sources.extend(
+                expand_braces(&raw)
+                    .iter()
+                    .map(|p| ContentSource::new(base, p)),
+            );
+        }
+    }
+
+    if let Ok(config) = fs::read_to_string(manifest_dir.join("tailwind.config.js")) {
+        for raw in config_content(&config) {
+            sources.extend(
+                expand_braces(&raw)
+                    .iter()
+                    .map(|p| ContentSource::new(manifest_dir, p)),
+            );
+        }
+    }
+
+    sources
+}
+
+/// How deep local `@import`s are followed from the input.
+const MAX_IMPORT_DEPTH: usize = 8;
+
+/// The input CSS followed by the local stylesheets it `@import`s (relative
+/// paths starting with `./` or `../`, and absolute paths), depth first.
+/// Unreadable files are skipped.
+pub fn stylesheets(input_path: &Path) -> Vec<(PathBuf, String)> {
+    let mut sheets = Vec::new();
+    collect_stylesheets(&normalize(input_path), 0, &mut sheets);
+    sheets
+}
+
+fn collect_stylesheets(path: &Path, depth: usize, sheets: &mut Vec<(PathBuf, String)>) {
+    if depth > MAX_IMPORT_DEPTH || sheets.iter().any(|(seen, _)| seen == path) {
+        return;
+    }
+    let Ok(css) = fs::read_to_string(path) else {
+        return;
+    };
+    let imports = local_imports(&css);
+    sheets.push((path.to_path_buf(), css));
+    let base = path.parent().unwrap_or(Path::new(""));
+    for import in imports {
+        collect_stylesheets(&normalize(&base.join(import)), depth + 1, sheets);
+    }
+}
+
+fn local_imports(css: &str) -> Vec<String> {
+    css.lines()
+        .filter_map(|line| line.trim().strip_prefix("@import"))
+        .filter_map(first_quoted)
+        .filter(|path| {
+            path.starts_with("./")
+                || path.starts_with("../")
+                || path.starts_with('/')
+                || Path::new(path).is_absolute()
+        })
+        .collect()
+}
+
+/// Directories that never hold content files and are expensive to walk.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", "dist"];
+
+/// Every file matched by the content sources, falling back to `src/**/*.rs`
+/// when none are declared (tailwind v4's automatic detection).
+pub fn content_files(manifest_dir: &Path, input_path: &Path) -> BTreeSet<PathBuf> {
+    let mut content = discover(manifest_dir, input_path);
+    if content.is_empty() {
+        content.push(ContentSource::new(manifest_dir, "src/**/*.rs"));
+    }
+    let mut files = BTreeSet::new();
+    for source in &content {
+        collect_files(&source.root(), source, &mut files);
+    }
+    files
+}
+
+fn collect_files(path: &Path, source: &ContentSource, files: &mut BTreeSet<PathBuf>) {
+    let Ok(meta) = fs::metadata(path) else {
+        return;
+    };
+    if meta.is_file() {
+        if source.matches(path) {
+            files.insert(path.to_path_buf());
+        }
+        return;
+    }
+    let Ok(entries) = fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        collect_files(&entry.path(), source, files);
+    }
+}
+
+/// Extract the paths of `@source "..."` directives, skipping `not` and
+/// `inline(...)` forms.
+fn source_directives(css: &str) -> Vec<String> {
+    css.lines()
+        .filter_map(|line| line.trim().strip_prefix("@source"))
+        .map(str::trim_start)
+        .filter(|rest| !rest.starts_with("not") && !rest.starts_with("inline"))
+        .filter_map(first_quoted)
+        .collect()
+}
+
+/// Extract the quoted entries of the `content` array in a tailwind v3 config.
+fn config_content(config: &str) -> Vec<String> {
+    let Some(start) = config.find("content") else {
+        return Vec::new();
+    };
+    let rest = &config[start..];
+    let Some(open) = rest.find('[') else {
+        return Vec::new();
+    };
+    let Some(close) = rest[open..].find(']') else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    let mut body = &rest[open + 1..open + close];
+    while let Some(value) = first_quoted(body) {
+        let consumed = body.find(value.as_str()).unwrap_or(0) + value.len() + 1;
+        entries.push(value);
+        body = &body[consumed.min(body.len())..];
+    }
+    entries
+}
+
+fn first_quoted(input: &str) -> Option<String> {
+    let start = input.find(['"', '\'', '`'])?;
+    let quote = input[start..].chars().next()?;
+    let rest = &input[start + 1..];
+    let end = rest.find(quote)?;
+    Some(rest[..end].to_string())
+}
+
+/// Expand `{a,b}` alternations, which `glob` does not understand.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_string()];
+    };
+    let Some(close) = pattern[open..].find('}').map(|i| open + i) else {
+        return vec![pattern.to_string()];
+    };
+    let (head, tail) = (&pattern[..open], &pattern[close + 1..]);
+    pattern[open + 1..close]
+        .split(',')
+        .flat_map(|alt| expand_braces(&format!("{head}{alt}{tail}")))
+        .collect()
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+/// Lexically resolve `.` and `..` so globs and paths compare reliably.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
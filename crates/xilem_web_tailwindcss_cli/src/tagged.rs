@@ -0,0 +1,88 @@
+use std::io::{BufRead as _, BufReader, Read, Write};
+use std::thread::{self, JoinHandle};
+
+use tracing::warn;
+
+use crate::ansi;
+
+/// A label prepended to every line a child process writes.
+#[derive(Debug, Clone, Copy)]
+pub struct Tag {
+    label: &'static str,
+    ansi: &'static str,
+    color: bool,
+}
+
+impl Tag {
+    pub fn tailwind(color: bool) -> Self {
+        Self {
+            label: "tailwind",
+            ansi: "36",
+            color,
+        }
+    }
+
+    pub fn trunk(color: bool) -> Self {
+        Self {
+            label: "trunk",
+            ansi: "35",
+            color,
+        }
+    }
+
+    fn prefix(self) -> String {
+        if self.color {
+            format!("\x1b[{}m[{}]\x1b[0m ", self.ansi, self.label)
+        } else {
+            format!("[{}] ", self.label)
+        }
+    }
+
+    /// Copy `reader` line by line to `writer`, tagging each line, until the
+    /// child closes its end of the pipe. Without color, the child's own
+    /// escape sequences are stripped as well.
+    pub fn forward(
+        self,
+        reader: impl Read + Send + 'static,
+        mut writer: impl Write + Send + 'static,
+    ) -> JoinHandle<()> {
+        thread::spawn(move || {
+            let prefix = self.prefix();
+            let mut reader = BufReader::new(reader);
+            let mut line = Vec::new();
+            loop {
+                line.clear();
+                match reader.read_until(b'\n', &mut line) {
+                    Ok(0) => break,
+                    Ok(_) => {}
+                    Err(err) => {
+                        warn!("Failed to read {} output: {err}", self.label);
+                        break;
+                    }
+                }
+                let text = String::from_utf8_lossy(&line);
+                let text = if self.color {
+                    text
+                } else {
+                    ansi::strip(&text).into_owned().into()
+                };
+                // One write per line keeps the two children's lines whole.
+                let tagged = format!("{prefix}{}\n", text.trim_end_matches(['\r', '\n']));
+                if writer.write_all(tagged.as_bytes()).is_err() {
+                    break;
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tag;
+
+    #[test]
+    fn prefixes_are_colored_on_request() {
+        assert_eq!(Tag::trunk(false).prefix(), "[trunk] ");
+        assert_eq!(Tag::tailwind(true).prefix(), "\x1b[36m[tailwind]\x1b[0m ");
+    }
+}
@@ -1,14 +1,53 @@
 use anyhow::{Context, Result, anyhow};
 use directories::ProjectDirs;
 use std::env;
+use std::ffi::OsStr;
+use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, Output, Stdio};
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::process::{Child, ChildStderr, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, Once, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
 use tracing::{debug, info, warn};
 
+use crate::ansi;
+use crate::archive::{self, AssetKind};
+use crate::compat;
+use crate::components;
+use crate::diagnostics::{self, Diagnostic};
+use crate::input::ResolvedInput;
+use crate::install;
+use crate::lockfile::{LOCKFILE_NAME, Lockfile, find_checksum, sha256_file, sha256_hex};
+use crate::output;
+use crate::sources;
+use crate::watch::{
+    BuildStats, InitialBuild, WatchOptions, WatchSnapshot, newest_modification,
+    parse_build_duration,
+};
+
 // Inspired by the Tailwind integration in dioxus.
 
+/// How often a supervised watch checks for new content files.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Output smaller than this almost certainly contains no utilities.
+const EMPTY_OUTPUT_BYTES: u64 = 512;
+
+/// How long a resolved `latest` tag is trusted before asking GitHub again.
+const LATEST_TAG_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
 static NO_DOWNLOADS_OVERRIDE: AtomicU8 = AtomicU8::new(2);
+static FROZEN: AtomicBool = AtomicBool::new(false);
+static NO_VERIFY: AtomicBool = AtomicBool::new(false);
+static FIX_INPUT: AtomicBool = AtomicBool::new(false);
+static COLOR: AtomicBool = AtomicBool::new(true);
+static RELEASE_REPO_OVERRIDE: OnceLock<String> = OnceLock::new();
+static RESOLVED_LATEST: OnceLock<String> = OnceLock::new();
+static CHILD_ENV: OnceLock<Vec<(String, String)>> = OnceLock::new();
+static TAILWIND_CONFIG: OnceLock<PathBuf> = OnceLock::new();
+static GITHUB_TOKEN: OnceLock<String> = OnceLock::new();
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
 
 #[derive(Debug, Default)]
 pub struct CliSettings;
@@ -25,6 +64,116 @@ impl CliSettings {
             _ => env_flag("XILEM_TAILWIND_NO_DOWNLOADS"),
         }
     }
+
+    /// Fail instead of creating or updating `tailwind.lock`.
+    pub fn set_frozen(value: bool) {
+        FROZEN.store(value, Ordering::Relaxed);
+    }
+
+    pub fn frozen() -> bool {
+        FROZEN.load(Ordering::Relaxed)
+    }
+
+    /// Skip checking downloads against the release's `sha256sums.txt`.
+    pub fn set_no_verify(value: bool) {
+        NO_VERIFY.store(value, Ordering::Relaxed);
+    }
+
+    pub fn no_verify() -> bool {
+        NO_VERIFY.load(Ordering::Relaxed)
+    }
+
+    pub fn set_fix_input(value: bool) {
+        FIX_INPUT.store(value, Ordering::Relaxed);
+    }
+
+    /// Whether duplicate tailwind imports are removed from the input.
+    pub fn fix_input() -> bool {
+        FIX_INPUT.load(Ordering::Relaxed)
+    }
+
+    /// Whether output may be colored; captured child output is stripped of
+    /// escape sequences otherwise.
+    pub fn set_color(value: bool) {
+        COLOR.store(value, Ordering::Relaxed);
+    }
+
+    pub fn color() -> bool {
+        COLOR.load(Ordering::Relaxed)
+    }
+
+    pub const DEFAULT_RELEASE_REPO: &'static str = "tailwindlabs/tailwindcss";
+
+    pub fn set_release_repo(repo: String) -> Result<()> {
+        validate_release_repo(&repo)?;
+        // Only the first call wins; the CLI sets this once at startup.
+        let _ = RELEASE_REPO_OVERRIDE.set(repo);
+        Ok(())
+    }
+
+    /// The GitHub `owner/repo` that publishes tailwindcss releases.
+    pub fn release_repo() -> Result<String> {
+        if let Some(repo) = RELEASE_REPO_OVERRIDE.get() {
+            return Ok(repo.clone());
+        }
+        match env::var("XILEM_TAILWIND_RELEASE_REPO") {
+            Ok(repo) if !repo.is_empty() => {
+                validate_release_repo(&repo).context("invalid XILEM_TAILWIND_RELEASE_REPO")?;
+                Ok(repo)
+            }
+            _ => Ok(Self::DEFAULT_RELEASE_REPO.to_string()),
+        }
+    }
+
+    /// Set the extra environment for spawned tailwind and trunk processes.
+    pub fn set_child_env(vars: Vec<(String, String)>) {
+        let _ = CHILD_ENV.set(vars);
+    }
+
+    pub fn child_env() -> &'static [(String, String)] {
+        CHILD_ENV.get().map_or(&[], Vec::as_slice)
+    }
+
+    /// Set the config file for tailwind builds; see
+    /// [`TailwindCli::with_config_flag`] for how it reaches the binary.
+    pub fn set_tailwind_config(path: PathBuf) {
+        let _ = TAILWIND_CONFIG.set(path);
+    }
+
+    pub fn tailwind_config() -> Option<&'static Path> {
+        TAILWIND_CONFIG.get().map(PathBuf::as_path)
+    }
+
+    pub fn set_github_token(token: String) {
+        let _ = GITHUB_TOKEN.set(token);
+    }
+
+    /// The token sent with GitHub requests: `--github-token`, then
+    /// `GITHUB_TOKEN`, then `GH_TOKEN`.
+    pub fn github_token() -> Option<String> {
+        if let Some(token) = GITHUB_TOKEN.get() {
+            return Some(token.clone());
+        }
+        ["GITHUB_TOKEN", "GH_TOKEN"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|token| !token.is_empty())
+    }
+}
+
+fn validate_release_repo(repo: &str) -> Result<()> {
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    };
+    match repo.split_once('/') {
+        Some((owner, name)) if valid_part(owner) && valid_part(name) => Ok(()),
+        _ => Err(anyhow!(
+            "release repo must look like `owner/repo`, got `{repo}`"
+        )),
+    }
 }
 
 #[derive(Debug, Default)]
@@ -41,96 +190,215 @@ impl Workspace {
 #[derive(Debug, Clone)]
 pub struct TailwindCli {
     version: String,
+    config: Option<PathBuf>,
 }
 
 impl TailwindCli {
     pub const LATEST_TAG: &'static str = "latest";
 
     pub fn new(version: String) -> Self {
-        Self { version }
+        Self {
+            version,
+            config: None,
+        }
+    }
+
+    /// Pass `config` to every build with `--config`. Only tailwind v3 has the
+    /// flag; v4 reads its config from an `@config` in the input instead.
+    #[must_use]
+    pub fn with_config_flag(mut self, config: PathBuf) -> Self {
+        self.config = Some(config);
+        self
     }
 
     pub fn latest() -> Self {
         Self::new(Self::LATEST_TAG.to_string())
     }
 
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Resolve the concrete tag that `latest` currently points to.
+    pub fn latest_release_tag() -> Result<String> {
+        let repo = CliSettings::release_repo()?;
+        let response = github_get(&format!("https://github.com/{repo}/releases/latest"))
+            .context("failed to query the latest tailwindcss release")?
+            .error_for_status()
+            .context("latest tailwindcss release lookup returned error status")?;
+        // GitHub redirects `/releases/latest` to `/releases/tag/<tag>`.
+        let url = response.url();
+        url.path()
+            .rsplit_once("/tag/")
+            .map(|(_, tag)| tag.to_string())
+            .filter(|tag| !tag.is_empty())
+            .ok_or_else(|| anyhow!("unexpected latest release URL {url}"))
+    }
+
     /// Use the latest Tailwind release when a tailwind input file is present.
-    pub fn autodetect(manifest_dir: &Path, input_path: Option<&PathBuf>) -> Option<Self> {
-        let input_exists = input_path.map_or_else(
-            || manifest_dir.join("tailwind.css").exists(),
-            |p| resolve_input_path(manifest_dir, p).exists(),
-        );
-        input_exists.then(Self::latest)
+    pub fn autodetect(input_path: &Path) -> Option<Self> {
+        input_path.exists().then(Self::latest)
     }
 
+    /// Build once and report where the CSS was written and how long it took.
     pub fn run_once(
         &self,
         manifest_dir: &Path,
-        input_path: Option<PathBuf>,
+        input_path: &Path,
         output_path: Option<PathBuf>,
         minify: bool,
-    ) -> Result<()> {
+    ) -> Result<BuildOutcome> {
         self.ensure_installed()?;
-        let output = self.run_with_output(manifest_dir, input_path, output_path, minify)?;
+        let output_path = resolve_paths(manifest_dir, input_path, output_path)?;
+        let temp_path = output::temp_path(&output_path);
+        let started = Instant::now();
+        let output = self
+            .run_with_output(manifest_dir, input_path, &temp_path, minify)
+            .and_then(|output| {
+                if output.status.success() {
+                    output::replace(&temp_path, &output_path)?;
+                    Ok(output)
+                } else {
+                    Err(build_failure(&output))
+                }
+            });
+        let duration = started.elapsed();
+        let output = output.inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp_path);
+        })?;
 
-        if !output.status.success() {
-            return Err(anyhow!("tailwindcss failed with status {}", output.status));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let diagnostics = diagnostics::parse(&stderr);
+        for diagnostic in &diagnostics {
+            diagnostic.emit();
         }
-
-        if !output.stderr.is_empty() {
-            warn!(
-                "Warnings while running tailwind: {}",
-                String::from_utf8_lossy(&output.stderr)
-            );
+        let warnings = diagnostics::unparsed(&stderr)
+            .filter(|line| !is_status_line(line))
+            .collect::<Vec<_>>();
+        if !warnings.is_empty() {
+            warn!("Warnings while running tailwind: {}", warnings.join("\n"));
         }
 
-        Ok(())
+        info!(
+            "Built {} in {}ms",
+            output_path.display(),
+            duration.as_millis()
+        );
+        warn_if_output_empty(&output_path, input_path);
+        Ok(BuildOutcome {
+            output_path,
+            duration,
+            diagnostics,
+        })
     }
 
     pub fn watch(
         &self,
         manifest_dir: &Path,
-        input_path: Option<PathBuf>,
-        output_path: Option<PathBuf>,
+        input: &ResolvedInput,
+        output_path: Option<&Path>,
+        options: WatchOptions,
     ) -> Result<()> {
+        let input_path = input.path();
         self.ensure_installed()?;
+        // Ctrl-C also reaches tailwind, which exits; the loop below then sees
+        // the flag and finishes with a summary instead of an error.
+        handle_interrupts();
+        let session = Arc::new(Mutex::new(BuildStats::default()));
 
-        let mut proc = self.run(manifest_dir, input_path, output_path, true, false)?;
-        let stdin = proc.stdin.take();
-        let status = proc.wait()?;
-        drop(stdin);
-
-        if !status.success() {
-            return Err(anyhow!("tailwindcss watch exited with status {status}"));
+        match options.initial_build {
+            InitialBuild::Tailwind => {}
+            InitialBuild::Force => {
+                components::generate(manifest_dir, input)?;
+                let outcome = self.run_once(
+                    manifest_dir,
+                    input_path,
+                    output_path.map(Path::to_path_buf),
+                    options.minify,
+                )?;
+                lock_stats(&session).record(outcome.duration);
+            }
+            InitialBuild::Skip => {
+                info!("Waiting for a change before starting tailwind watch");
+                let baseline = newest_modification(manifest_dir, input_path);
+                while newest_modification(manifest_dir, input_path) <= baseline {
+                    if interrupted() {
+                        return Ok(());
+                    }
+                    thread::sleep(WATCH_POLL_INTERVAL);
+                }
+            }
         }
 
-        Ok(())
-    }
+        loop {
+            components::generate(manifest_dir, input)?;
+            let mut proc = self.run_with_stdio(
+                manifest_dir,
+                input_path,
+                output_path.map(Path::to_path_buf),
+                true,
+                options.minify,
+                Stdio::inherit(),
+                Stdio::piped(),
+            )?;
+            let stdin = proc.stdin.take();
+            let supervisor = proc.stderr.take().map(|stderr| {
+                let session = Arc::clone(&session);
+                thread::spawn(move || supervise_watch_output(stderr, &session, options.clear))
+            });
 
-    pub fn run(
-        &self,
-        manifest_dir: &Path,
-        input_path: Option<PathBuf>,
-        output_path: Option<PathBuf>,
-        watch: bool,
-        minify: bool,
-    ) -> Result<Child> {
-        self.run_with_stdio(
-            manifest_dir,
-            input_path,
-            output_path,
-            watch,
-            minify,
-            Stdio::null(),
-            Stdio::null(),
-        )
+            let mut snapshot = WatchSnapshot::capture(manifest_dir, input_path);
+            let status = loop {
+                if let Some(status) = proc.try_wait()? {
+                    break Some(status);
+                }
+                if interrupted() {
+                    let _ = proc.kill();
+                    break Some(proc.wait()?);
+                }
+                thread::sleep(WATCH_POLL_INTERVAL);
+                let next = WatchSnapshot::capture(manifest_dir, input_path);
+                // Tailwind rebuilds by itself once an edited component
+                // definition lands in components.css.
+                if snapshot.content_changed(&next) {
+                    if let Err(err) = components::generate(manifest_dir, input) {
+                        warn!("Failed to regenerate components: {err:#}");
+                    }
+                }
+                if let Some(reason) = snapshot.restart_reason(&next) {
+                    info!("{reason}; restarting tailwind watch");
+                    let _ = proc.kill();
+                    proc.wait()?;
+                    break None;
+                }
+                snapshot = next;
+            };
+            drop(stdin);
+            if let Some(supervisor) = supervisor {
+                supervisor
+                    .join()
+                    .map_err(|_| anyhow!("tailwindcss output thread panicked"))??;
+            }
+
+            if interrupted() {
+                log_watch_summary(&lock_stats(&session), manifest_dir, output_path);
+                return Ok(());
+            }
+            match status {
+                Some(status) if !status.success() => {
+                    return Err(anyhow!("tailwindcss watch exited with status {status}"));
+                }
+                Some(_) => return Ok(()),
+                None => {}
+            }
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn run_with_stdio(
         &self,
         manifest_dir: &Path,
-        input_path: Option<PathBuf>,
+        input_path: &Path,
         output_path: Option<PathBuf>,
         watch: bool,
         minify: bool,
@@ -138,8 +406,7 @@ impl TailwindCli {
         stderr: Stdio,
     ) -> Result<Child> {
         let binary_path = self.get_binary_path()?;
-        let input_path = resolve_input(manifest_dir, input_path);
-        let output_path = resolve_output(manifest_dir, output_path)?;
+        let output_path = resolve_paths(manifest_dir, input_path, output_path)?;
 
         debug!("Spawning tailwindcss@{} with args: {:?}", self.version, {
             let mut args = vec![
@@ -155,16 +422,21 @@ impl TailwindCli {
             if minify {
                 args.push("--minify".to_string());
             }
+            args.extend(
+                self.config_args()
+                    .map(|arg| arg.to_string_lossy().to_string()),
+            );
             args
         });
 
-        let mut cmd = Command::new(binary_path);
+        let mut cmd = tailwind_command(&binary_path);
         let proc = cmd
             .arg("--input")
             .arg(input_path)
             .arg("--output")
             .arg(output_path)
             .args(watch.then_some("--watch"))
+            .args(self.config_args())
             .args(minify.then_some("--minify"))
             .current_dir(manifest_dir)
             .stdin(Stdio::piped())
@@ -179,20 +451,19 @@ impl TailwindCli {
     pub fn run_with_output(
         &self,
         manifest_dir: &Path,
-        input_path: Option<PathBuf>,
-        output_path: Option<PathBuf>,
+        input_path: &Path,
+        output_path: &Path,
         minify: bool,
     ) -> Result<Output> {
         let binary_path = self.get_binary_path()?;
-        let input_path = resolve_input(manifest_dir, input_path);
-        let output_path = resolve_output(manifest_dir, output_path)?;
 
-        let output = Command::new(binary_path)
+        let output = tailwind_command(&binary_path)
             .arg("--input")
             .arg(input_path)
             .arg("--output")
             .arg(output_path)
             .args(minify.then_some("--minify"))
+            .args(self.config_args())
             .current_dir(manifest_dir)
             .stdin(Stdio::null())
             .stdout(Stdio::piped())
@@ -203,72 +474,317 @@ impl TailwindCli {
         Ok(output)
     }
 
+    /// `--config <path>` when a tailwind config was given.
+    fn config_args(&self) -> impl Iterator<Item = &OsStr> {
+        self.config
+            .iter()
+            .flat_map(|config| [OsStr::new("--config"), config.as_os_str()])
+    }
+
+    /// The major version of the installed binary, from `tailwindcss --help`.
+    pub fn major_version(&self) -> Result<Option<u32>> {
+        let binary_path = self.get_binary_path()?;
+        let output = tailwind_command(&binary_path)
+            .arg("--help")
+            .stdin(Stdio::null())
+            .output()
+            .context("failed to run tailwindcss --help")?;
+        let help = [output.stdout, output.stderr].concat();
+        Ok(compat::parse_help_major(&String::from_utf8_lossy(&help)))
+    }
+
     pub fn get_binary_path(&self) -> Result<PathBuf> {
         if CliSettings::prefer_no_downloads() {
-            which::which("tailwindcss")
-                .with_context(|| format!("missing tailwindcss@{}", self.version))
+            find_on_path().with_context(|| format!("missing tailwindcss@{}", self.version))
         } else {
-            let installed_name = self.installed_bin_name();
+            let installed_name = Self::installed_bin_name(&self.concrete_version()?);
             let install_dir = Self::install_dir()?;
             Ok(install_dir.join(installed_name))
         }
     }
 
     pub fn ensure_installed(&self) -> Result<()> {
-        if self.get_binary_path()?.exists() {
+        let binary_path = self.get_binary_path()?;
+        if binary_path.exists() {
             return Ok(());
         }
-        info!("Installing tailwindcss@{}", self.version);
-        self.install_github()
+        let tag = self.concrete_version()?;
+        info!("Installing tailwindcss@{tag}");
+        install::install_once(&binary_path, |destination| {
+            Self::install_github(&tag, destination)
+        })
+    }
+
+    /// Install the binary recorded in the project's `tailwind.lock`, creating
+    /// or updating the lock as needed, and return the locked version.
+    ///
+    /// `latest` follows the locked tag; an explicit version replaces it. A
+    /// cached binary whose checksum differs from the lock is downloaded again.
+    /// Binaries found on `PATH` with `--no-downloads` are not locked.
+    pub fn ensure_locked(&self, manifest_dir: &Path) -> Result<Self> {
+        if CliSettings::prefer_no_downloads() {
+            debug!("Skipping {LOCKFILE_NAME} for a tailwindcss from PATH");
+            return Ok(self.clone());
+        }
+        let platform = Self::downloaded_bin_name()
+            .ok_or_else(|| anyhow!("no available GitHub binary for this platform"))?;
+        let frozen = CliSettings::frozen();
+        let lock = Lockfile::read(manifest_dir)?;
+        let tailwind = match &lock {
+            Some(lock) if self.follows(lock) => Self::new(lock.version.clone()),
+            Some(lock) if frozen => {
+                return Err(anyhow!(
+                    "{LOCKFILE_NAME} pins tailwindcss@{} but {} was requested; rerun without --frozen to update it",
+                    lock.version,
+                    self.version
+                ));
+            }
+            _ => self.clone(),
+        };
+        let version = tailwind.concrete_version()?;
+
+        tailwind.ensure_installed()?;
+        let binary_path = tailwind.get_binary_path()?;
+        sync_lock(
+            manifest_dir,
+            lock.as_ref(),
+            &version,
+            platform,
+            &binary_path,
+            frozen,
+            || tailwind.ensure_installed(),
+        )?;
+        Ok(tailwind)
     }
 
-    fn installed_bin_name(&self) -> String {
-        let mut name = format!("tailwindcss-{}", self.version);
+    /// The version `tailwind.lock` resolves this request to, without creating
+    /// or updating the lock, for commands that only query the binary.
+    pub fn locked(&self, manifest_dir: &Path) -> Result<Self> {
+        Ok(match Lockfile::read(manifest_dir)? {
+            Some(lock) if self.follows(&lock) => Self::new(lock.version),
+            _ => self.clone(),
+        })
+    }
+
+    /// Download the release again and compare it with the installed binary,
+    /// replacing the binary on a mismatch only when `fix` is set.
+    ///
+    /// The version follows `tailwind.lock` like [`Self::ensure_locked`], but
+    /// the lock itself is never modified.
+    pub fn verify(&self, manifest_dir: &Path, fix: bool) -> Result<Verification> {
+        if CliSettings::prefer_no_downloads() {
+            return Err(anyhow!(
+                "verify compares downloaded binaries; it cannot be used with --no-downloads"
+            ));
+        }
+        let platform = Self::downloaded_bin_name()
+            .ok_or_else(|| anyhow!("no available GitHub binary for this platform"))?;
+        let lock = Lockfile::read(manifest_dir)?;
+        let tailwind = match &lock {
+            Some(lock) if self.follows(lock) => Self::new(lock.version.clone()),
+            _ => self.clone(),
+        };
+        let version = tailwind.concrete_version()?;
+        let binary_path = tailwind.get_binary_path()?;
+        if !binary_path.exists() {
+            return Err(anyhow!(
+                "tailwindcss@{version} is not installed at {}",
+                binary_path.display()
+            ));
+        }
+        let installed = sha256_file(&binary_path)?;
+
+        let temp_path =
+            install::with_suffix(&binary_path, &format!("verify-{}", std::process::id()));
+        let downloaded =
+            Self::install_github(&version, &temp_path).and_then(|()| sha256_file(&temp_path));
+        let downloaded = match downloaded {
+            Ok(downloaded) => downloaded,
+            Err(err) => {
+                let _ = std::fs::remove_file(&temp_path);
+                return Err(err);
+            }
+        };
+        let fixed = fix && installed != downloaded;
+        if fixed {
+            std::fs::rename(&temp_path, &binary_path)
+                .with_context(|| format!("failed to replace {}", binary_path.display()))?;
+        } else {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+
+        let locked = lock
+            .filter(|lock| lock.version == version)
+            .and_then(|mut lock| lock.sha256.remove(&platform));
+        Ok(Verification {
+            version,
+            binary_path,
+            installed,
+            downloaded,
+            locked,
+            fixed,
+        })
+    }
+
+    /// Whether this requested version resolves to the one in `lock`.
+    fn follows(&self, lock: &Lockfile) -> bool {
+        self.version == Self::LATEST_TAG || self.version == lock.version
+    }
+
+    /// The release tag to install, with `latest` resolved to a concrete tag so
+    /// every install is keyed by the version it really is.
+    ///
+    /// The resolved tag is cached for a day and reused when GitHub cannot be
+    /// reached.
+    fn concrete_version(&self) -> Result<String> {
+        if self.version != Self::LATEST_TAG {
+            return Ok(self.version.clone());
+        }
+        if let Some(tag) = RESOLVED_LATEST.get() {
+            return Ok(tag.clone());
+        }
+        let tag = Self::resolve_latest()?;
+        Ok(RESOLVED_LATEST.get_or_init(|| tag).clone())
+    }
+
+    fn resolve_latest() -> Result<String> {
+        let cache_path = Self::install_dir()?.join("latest-tag");
+        let cached = std::fs::read_to_string(&cache_path)
+            .ok()
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty());
+        let fresh = std::fs::metadata(&cache_path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_some_and(|age| age < LATEST_TAG_TTL);
+        if let (true, Some(tag)) = (fresh, &cached) {
+            return Ok(tag.clone());
+        }
+
+        match Self::latest_release_tag() {
+            Ok(tag) => {
+                if let Some(parent) = cache_path.parent() {
+                    let _ = std::fs::create_dir_all(parent);
+                }
+                let _ = std::fs::write(&cache_path, &tag);
+                Ok(tag)
+            }
+            Err(err) => match cached.or_else(Self::newest_cached_tag) {
+                Some(tag) => {
+                    warn!("Could not check for a newer tailwindcss ({err:#}); using {tag}");
+                    Ok(tag)
+                }
+                None => Err(err),
+            },
+        }
+    }
+
+    /// The highest tag among the binaries cached for this host.
+    fn newest_cached_tag() -> Option<String> {
+        let (_, binaries) = Self::cached_binaries().ok()?;
+        let host = target_lexicon::HOST.to_string();
+        binaries
+            .into_iter()
+            .filter(|binary| binary.target == host)
+            .map(|binary| binary.version)
+            .max_by_key(|tag| release_order(tag))
+    }
+
+    /// The cached binary's file name, keyed by tag and host target triple so
+    /// a data directory shared between machines never runs a binary built for
+    /// another architecture.
+    fn installed_bin_name(tag: &str) -> String {
+        let mut name = format!("tailwindcss-{tag}-{}", target_lexicon::HOST);
         if cfg!(windows) {
             name = format!("{name}.exe");
         }
         name
     }
 
-    fn install_github(&self) -> Result<()> {
-        debug!(
-            "Attempting to install tailwindcss@{} from GitHub",
-            self.version
-        );
+    fn install_github(tag: &str, binary_path: &Path) -> Result<()> {
+        debug!("Attempting to install tailwindcss@{tag} from GitHub");
 
-        let url = self.git_install_url().ok_or_else(|| {
-            anyhow!(
-                "no available GitHub binary for tailwindcss@{}",
-                self.version
-            )
-        })?;
+        let url = Self::git_install_url(tag)?;
 
-        let response = reqwest::blocking::get(url)
+        let response = github_get(&url)
             .context("failed to download tailwindcss")?
             .error_for_status()
             .context("tailwindcss download returned error status")?;
-
-        let binary_path = self.get_binary_path()?;
-        if let Some(parent) = binary_path.parent() {
-            std::fs::create_dir_all(parent).context("failed to create tailwindcss directory")?;
-        }
+        // The final URL after redirects carries the real asset name, which
+        // is the name `sha256sums.txt` lists for an archive.
+        let asset_url = response.url().to_string();
+        let asset = archive::asset_name(&asset_url).to_string();
 
         let bytes = response
             .bytes()
-            .context("failed to read tailwindcss body")?;
-        std::fs::write(&binary_path, &bytes).context("failed to write tailwindcss binary")?;
+            .context("failed to read tailwindcss body")?
+            .to_vec();
+        if CliSettings::no_verify() {
+            warn!("Not verifying the checksum of tailwindcss@{tag} (--no-verify)");
+        } else {
+            Self::verify_download(tag, &asset, &bytes)?;
+        }
+        let kind = AssetKind::detect(&asset_url, &bytes);
+        if kind != AssetKind::Binary {
+            debug!("Extracting tailwindcss from {kind:?} asset");
+        }
+        let bytes = archive::extract_binary(kind, bytes)?;
+        std::fs::write(binary_path, &bytes).context("failed to write tailwindcss binary")?;
 
         #[cfg(unix)]
         {
             use std::os::unix::fs::PermissionsExt;
             let mut perms = binary_path.metadata()?.permissions();
             perms.set_mode(0o755);
-            std::fs::set_permissions(&binary_path, perms)?;
+            std::fs::set_permissions(binary_path, perms)?;
         }
 
         Ok(())
     }
 
+    /// Check downloaded asset bytes against the `sha256sums.txt` published
+    /// with the release. `tag` must be concrete; `latest` could move between
+    /// the two requests.
+    ///
+    /// Older releases publish no sums file. Those are only checked against
+    /// `tailwind.lock`, which records the binary's hash on first install.
+    fn verify_download(tag: &str, asset: &str, bytes: &[u8]) -> Result<()> {
+        let repo = CliSettings::release_repo()?;
+        let url = format!("https://github.com/{repo}/releases/download/{tag}/sha256sums.txt");
+        let context = || {
+            format!(
+                "failed to download sha256sums.txt for tailwindcss@{tag}; pass --no-verify to skip the check"
+            )
+        };
+        let response = github_get(&url).with_context(context)?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            warn!(
+                "tailwindcss@{tag} publishes no sha256sums.txt; relying on the checksum in {LOCKFILE_NAME}"
+            );
+            return Ok(());
+        }
+        let sums = response
+            .error_for_status()
+            .and_then(reqwest::blocking::Response::text)
+            .with_context(context)?;
+        // GitHub itself redirects to a storage URL without the asset name, so
+        // fall back to the name that was requested.
+        let requested = Self::downloaded_bin_name().unwrap_or_default();
+        let (asset, expected) = [asset, requested.as_str()]
+            .into_iter()
+            .find_map(|name| find_checksum(&sums, name).map(|hash| (name, hash)))
+            .ok_or_else(|| anyhow!("sha256sums.txt for tailwindcss@{tag} does not list {asset}"))?;
+        let actual = sha256_hex(bytes);
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(anyhow!(
+                "downloaded {asset} for tailwindcss@{tag} has checksum {actual}, but the release lists {expected}"
+            ));
+        }
+        debug!("Verified {asset} for tailwindcss@{tag} against sha256sums.txt");
+        Ok(())
+    }
+
     fn downloaded_bin_name() -> Option<String> {
         let platform = match target_lexicon::HOST.operating_system {
             target_lexicon::OperatingSystem::Linux => "linux",
@@ -291,63 +807,483 @@ impl TailwindCli {
         Some(format!("tailwindcss-{platform}-{arch}"))
     }
 
+    /// Binaries from the default release repo live in `tailwind/`; a
+    /// `--release-repo` fork gets `tailwind-forks/<owner>/<repo>`, so the same
+    /// tag from two repos, and each repo's resolved `latest`, are kept apart.
     fn install_dir() -> Result<PathBuf> {
-        Ok(Workspace::xilem_data_dir()?.join("tailwind"))
+        let data_dir = Workspace::xilem_data_dir()?;
+        let repo = CliSettings::release_repo()?;
+        if repo == CliSettings::DEFAULT_RELEASE_REPO {
+            Ok(data_dir.join("tailwind"))
+        } else {
+            Ok(data_dir.join("tailwind-forks").join(repo))
+        }
     }
 
-    fn git_install_url(&self) -> Option<String> {
-        let binary = Self::downloaded_bin_name()?;
-        if self.version == Self::LATEST_TAG {
-            return Some(format!(
-                "https://github.com/tailwindlabs/tailwindcss/releases/latest/download/{binary}"
-            ));
+    /// The install directory and the binaries cached in it, sorted by file
+    /// name. A missing directory is an empty cache.
+    pub fn cached_binaries() -> Result<(PathBuf, Vec<CachedBinary>)> {
+        let install_dir = Self::install_dir()?;
+        let mut binaries = Vec::new();
+        for entry in read_install_dir(&install_dir)? {
+            let name = entry.file_name();
+            let Some((version, target)) = parse_installed_bin_name(&name.to_string_lossy()) else {
+                continue;
+            };
+            let size = entry.metadata().map_or(0, |meta| meta.len());
+            binaries.push(CachedBinary {
+                path: entry.path(),
+                version,
+                target,
+                size,
+            });
         }
-        Some(format!(
-            "https://github.com/tailwindlabs/tailwindcss/releases/download/{}/{}",
-            self.version, binary
+        binaries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok((install_dir, binaries))
+    }
+
+    /// Delete everything in the install directory, including partial
+    /// downloads, and return the install directory and the removed paths.
+    pub fn clear_cache() -> Result<(PathBuf, Vec<PathBuf>)> {
+        let install_dir = Self::install_dir()?;
+        let removed = clear_dir(&install_dir)?;
+        Ok((install_dir, removed))
+    }
+
+    fn git_install_url(tag: &str) -> Result<String> {
+        let binary = Self::downloaded_bin_name()
+            .ok_or_else(|| anyhow!("no available GitHub binary for tailwindcss@{tag}"))?;
+        let repo = CliSettings::release_repo()?;
+        Ok(format!(
+            "https://github.com/{repo}/releases/download/{tag}/{binary}"
         ))
     }
 }
 
-fn resolve_input(manifest_dir: &Path, input_path: Option<PathBuf>) -> PathBuf {
-    input_path.map_or_else(
-        || manifest_dir.join("tailwind.css"),
-        |p| resolve_input_path(manifest_dir, &p),
-    )
+/// The entries of the install directory. A missing directory has none.
+fn read_install_dir(install_dir: &Path) -> Result<Vec<std::fs::DirEntry>> {
+    let entries = match std::fs::read_dir(install_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", install_dir.display()));
+        }
+    };
+    entries
+        .collect::<std::io::Result<_>>()
+        .with_context(|| format!("failed to read {}", install_dir.display()))
 }
 
-fn resolve_output(manifest_dir: &Path, output_path: Option<PathBuf>) -> Result<PathBuf> {
-    let output_path = output_path.map_or_else(
-        || manifest_dir.join("assets").join("tailwind.css"),
-        |p| resolve_output_path(manifest_dir, &p),
-    );
-    let parent = output_path
-        .parent()
-        .ok_or_else(|| anyhow!("tailwind output path has no parent"))?;
-    if !parent.exists() {
-        std::fs::create_dir_all(parent).context("failed to create tailwindcss output directory")?;
+/// Delete every file and directory in `install_dir`, returning the removed
+/// paths sorted.
+fn clear_dir(install_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    for entry in read_install_dir(install_dir)? {
+        let path = entry.path();
+        let result = if entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        result.with_context(|| format!("failed to remove {}", path.display()))?;
+        removed.push(path);
     }
-    Ok(output_path)
+    removed.sort();
+    Ok(removed)
 }
 
-fn resolve_input_path(manifest_dir: &Path, path: &Path) -> PathBuf {
-    if path.is_absolute() {
-        return path.to_path_buf();
+/// A tailwindcss binary in the install directory.
+#[derive(Debug)]
+pub struct CachedBinary {
+    pub path: PathBuf,
+    pub version: String,
+    pub target: String,
+    pub size: u64,
+}
+
+/// Check the installed `binary_path` against `lock` and record its checksum,
+/// for [`TailwindCli::ensure_locked`] once the version is resolved.
+///
+/// A binary that does not match the lock is removed and `reinstall`ed once.
+/// With `frozen`, a lock that would change is an error instead.
+fn sync_lock(
+    manifest_dir: &Path,
+    lock: Option<&Lockfile>,
+    version: &str,
+    platform: String,
+    binary_path: &Path,
+    frozen: bool,
+    reinstall: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let expected = lock
+        .filter(|lock| lock.version == version)
+        .and_then(|lock| lock.sha256.get(&platform));
+    let mut actual = sha256_file(binary_path)?;
+    if let Some(expected) = expected {
+        if &actual != expected {
+            warn!(
+                "Cached tailwindcss@{version} does not match {LOCKFILE_NAME}; downloading it again"
+            );
+            std::fs::remove_file(binary_path)
+                .with_context(|| format!("failed to remove {}", binary_path.display()))?;
+            reinstall()?;
+            actual = sha256_file(binary_path)?;
+            if &actual != expected {
+                return Err(anyhow!(
+                    "tailwindcss@{version} has checksum {actual}, but {LOCKFILE_NAME} expects {expected}"
+                ));
+            }
+        }
     }
 
-    let manifest_candidate = manifest_dir.join(path);
-    if manifest_candidate.exists() {
-        return manifest_candidate;
+    let mut updated = lock
+        .filter(|lock| lock.version == version)
+        .cloned()
+        .unwrap_or_else(|| Lockfile {
+            version: version.to_string(),
+            sha256: std::collections::BTreeMap::new(),
+        });
+    updated.sha256.insert(platform, actual);
+    if lock != Some(&updated) {
+        if frozen {
+            return Err(anyhow!(
+                "{LOCKFILE_NAME} needs to be updated for tailwindcss@{version}, but --frozen was passed"
+            ));
+        }
+        updated.write(manifest_dir)?;
+        info!("Locked tailwindcss@{version} in {LOCKFILE_NAME}");
     }
+    Ok(())
+}
 
-    if let Ok(cwd) = env::current_dir() {
-        let cwd_candidate = cwd.join(path);
-        if cwd_candidate.exists() {
-            return cwd_candidate;
+/// Sort key for release tags: `v4.1.10` after `v4.1.9`, and a prerelease
+/// (`v4.0.0-beta.1`) before its release.
+fn release_order(tag: &str) -> (Vec<u64>, bool) {
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+    let (core, prerelease) = match tag.split_once('-') {
+        Some((core, prerelease)) => (core, Some(prerelease)),
+        None => (tag, None),
+    };
+    let numbers = core
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect();
+    (numbers, prerelease.is_none())
+}
+
+/// Split an installed binary's name into its tag and target triple; see
+/// [`TailwindCli::installed_bin_name`]. Tags may contain dashes
+/// (`v4.0.0-beta.1`), so the triple is the first suffix that parses as one.
+/// Download temp files and other entries give `None`.
+fn parse_installed_bin_name(name: &str) -> Option<(String, String)> {
+    let rest = name.strip_prefix("tailwindcss-")?;
+    let rest = rest.strip_suffix(".exe").unwrap_or(rest);
+    rest.match_indices('-').find_map(|(index, _)| {
+        let target = &rest[index + 1..];
+        let triple = target.parse::<target_lexicon::Triple>().ok()?;
+        (triple.architecture != target_lexicon::Architecture::Unknown)
+            .then(|| (rest[..index].to_string(), target.to_string()))
+    })
+}
+
+/// The checksums compared by [`TailwindCli::verify`].
+#[derive(Debug)]
+pub struct Verification {
+    pub version: String,
+    pub binary_path: PathBuf,
+    pub installed: String,
+    pub downloaded: String,
+    /// The checksum `tailwind.lock` records for this platform, if any.
+    pub locked: Option<String>,
+    /// Whether the installed binary was replaced with the download.
+    pub fixed: bool,
+}
+
+impl Verification {
+    pub fn matches(&self) -> bool {
+        self.installed == self.downloaded
+    }
+}
+
+/// The result of a successful one-off build.
+#[derive(Debug)]
+pub struct BuildOutcome {
+    pub output_path: PathBuf,
+    pub duration: Duration,
+    /// Warnings tailwind printed while building.
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// The error for a failed build. Diagnostics parsed from tailwind's stderr are
+/// logged, and the lines that could not be parsed go in the error.
+fn build_failure(output: &Output) -> anyhow::Error {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for diagnostic in &diagnostics::parse(&stderr) {
+        diagnostic.emit();
+    }
+    let details = diagnostics::unparsed(&stderr)
+        .filter(|line| !is_status_line(line))
+        .collect::<Vec<_>>()
+        .join("\n");
+    if details.is_empty() {
+        anyhow!("tailwindcss failed with status {}", output.status)
+    } else {
+        anyhow!(
+            "tailwindcss failed with status {}:\n{details}",
+            output.status
+        )
+    }
+}
+
+/// Forward tailwind's watch output, replacing its `Done in` lines with
+/// rebuild timings and a periodic rolling average.
+///
+/// With `clear`, the terminal is cleared when the first line after a finished
+/// build arrives, so the screen only shows the latest rebuild.
+fn supervise_watch_output(
+    stderr: ChildStderr,
+    stats: &Mutex<BuildStats>,
+    clear: bool,
+) -> Result<()> {
+    let mut clear_pending = false;
+    for line in BufReader::new(stderr).lines() {
+        let line = line.context("failed to read tailwindcss output")?;
+        if clear_pending && !line.trim().is_empty() {
+            clear_terminal();
+            clear_pending = false;
+        }
+        if let Some(duration) = parse_build_duration(&line) {
+            clear_pending = clear;
+            let mut stats = lock_stats(stats);
+            stats.record(duration);
+            info!("Rebuilt in {}ms", duration.as_millis());
+            if stats.should_report() {
+                if let Some(average) = stats.rolling_average() {
+                    info!(
+                        "Average rebuild time: {}ms over {} builds",
+                        average.as_millis(),
+                        stats.count()
+                    );
+                }
+            }
+        } else if !is_status_line(&line) {
+            if CliSettings::color() {
+                eprintln!("{line}");
+            } else {
+                eprintln!("{}", ansi::strip(&line));
+            }
         }
     }
+    Ok(())
+}
 
-    manifest_candidate
+/// Record Ctrl-C instead of exiting straight away, so long-running commands
+/// stop their children and return normally, removing the temporary inputs
+/// and configs they wrote. Installing it again is a no-op.
+pub fn handle_interrupts() {
+    static INSTALLED: Once = Once::new();
+    INSTALLED.call_once(|| {
+        if let Err(err) = ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::SeqCst)) {
+            warn!("Failed to install the Ctrl-C handler: {err}");
+        }
+    });
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+fn lock_stats(stats: &Mutex<BuildStats>) -> std::sync::MutexGuard<'_, BuildStats> {
+    stats
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Summarize a watch session that was stopped with Ctrl-C.
+fn log_watch_summary(stats: &BuildStats, manifest_dir: &Path, output_path: Option<&Path>) {
+    info!("Stopped watching after {} builds", stats.count());
+    if let Some(average) = stats.average() {
+        info!(
+            "  build time {}ms total, {}ms on average",
+            stats.total().as_millis(),
+            average.as_millis()
+        );
+    }
+    let Ok(output_path) = resolve_output(manifest_dir, output_path.map(Path::to_path_buf)) else {
+        return;
+    };
+    if let Ok(meta) = std::fs::metadata(&output_path) {
+        info!("  {} is {} bytes", output_path.display(), meta.len());
+    }
+}
+
+fn clear_terminal() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x1b[2J\x1b[H");
+    let _ = stdout.flush();
+}
+
+/// Flag the most common misconfiguration: a build that succeeds but
+/// generates no utilities because no content or directives matched.
+fn warn_if_output_empty(output_path: &Path, input_path: &Path) {
+    let Ok(meta) = std::fs::metadata(output_path) else {
+        return;
+    };
+    if meta.len() < EMPTY_OUTPUT_BYTES {
+        warn!(
+            "{} is only {} bytes, so tailwind probably generated no utilities. Check that the content sources (`@source` or `content`) cover your .rs files and that {} contains `@import \"tailwindcss\";`",
+            output_path.display(),
+            meta.len(),
+            input_path.display()
+        );
+    }
+}
+
+/// Tailwind's version banner, blank lines and `Done in` timings.
+fn is_status_line(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with("≈ tailwindcss") || parse_build_duration(line).is_some()
+}
+
+/// Look up `tailwindcss` on `PATH`.
+///
+/// On Windows an npm install only provides `tailwindcss.cmd`/`.bat` shims, so
+/// those are tried explicitly in case `PATHEXT` does not list them.
+fn find_on_path() -> which::Result<PathBuf> {
+    let result = which::which("tailwindcss");
+    if cfg!(windows) && result.is_err() {
+        if let Some(path) = find_shim(WINDOWS_SHIM_EXTENSIONS, |name| which::which(name).ok()) {
+            return Ok(path);
+        }
+    }
+    result
+}
+
+/// Extensions of the Windows shims tried when `tailwindcss` is not found, in
+/// order.
+const WINDOWS_SHIM_EXTENSIONS: &[&str] = &["cmd", "bat", "exe"];
+
+/// The first `tailwindcss.<ext>` that `lookup` finds, trying `extensions` in
+/// order.
+fn find_shim(
+    extensions: &[&str],
+    mut lookup: impl FnMut(&str) -> Option<PathBuf>,
+) -> Option<PathBuf> {
+    extensions
+        .iter()
+        .find_map(|ext| lookup(&format!("tailwindcss.{ext}")))
+}
+
+/// GET a GitHub URL, authenticated when a token is configured so shared CI
+/// runners are not rate limited. reqwest drops the header on redirects to
+/// another host, such as the release asset CDN.
+fn github_get(url: &str) -> reqwest::Result<reqwest::blocking::Response> {
+    let mut request = reqwest::blocking::Client::new().get(url);
+    if let Some(token) = CliSettings::github_token() {
+        if let Ok(mut value) = reqwest::header::HeaderValue::try_from(format!("Bearer {token}")) {
+            value.set_sensitive(true);
+            request = request.header(reqwest::header::AUTHORIZATION, value);
+        } else {
+            warn!("Ignoring a GitHub token that is not a valid header value");
+        }
+    }
+    request.send()
+}
+
+/// Batch-file shims cannot be spawned directly and must run through `cmd /C`
+/// so their arguments are forwarded.
+fn tailwind_command(binary_path: &Path) -> Command {
+    let mut cmd = if runs_through_cmd(binary_path, cfg!(windows)) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(binary_path);
+        cmd
+    } else {
+        Command::new(binary_path)
+    };
+    cmd.envs(CliSettings::child_env().iter().map(|(k, v)| (k, v)));
+    cmd
+}
+
+/// Whether `binary_path` is a `.cmd`/`.bat` shim that has to be wrapped in
+/// `cmd /C`, which only applies on Windows.
+fn runs_through_cmd(binary_path: &Path, windows: bool) -> bool {
+    windows
+        && binary_path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("cmd") || ext.eq_ignore_ascii_case("bat"))
+}
+
+fn resolve_paths(
+    manifest_dir: &Path,
+    input_path: &Path,
+    output_path: Option<PathBuf>,
+) -> Result<PathBuf> {
+    let output_path = resolve_output(manifest_dir, output_path)?;
+    if canonical_path(input_path) == canonical_path(&output_path) {
+        return Err(anyhow!(
+            "output {} is the tailwind input; building would overwrite it. Pass a different --output.",
+            output_path.display()
+        ));
+    }
+    if !output_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("css"))
+    {
+        warn!(
+            "Output {} does not have a .css extension",
+            output_path.display()
+        );
+    }
+    warn_if_output_watched(manifest_dir, input_path, &output_path);
+    Ok(output_path)
+}
+
+/// Canonicalize a path that may not exist yet via its parent directory.
+fn canonical_path(path: &Path) -> PathBuf {
+    if let Ok(path) = path.canonicalize() {
+        return path;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map_or_else(|_| path.to_path_buf(), |parent| parent.join(name)),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Tailwind rebuilds whenever a content file changes, so an output inside a
+/// content source makes every build trigger the next one.
+fn warn_if_output_watched(manifest_dir: &Path, input_path: &Path, output_path: &Path) {
+    let overlapping = sources::discover(manifest_dir, input_path)
+        .into_iter()
+        .find(|source| source.matches(output_path));
+    if let Some(source) = overlapping {
+        warn!(
+            "Output {} is inside content source {}; tailwind will see its own writes and rebuild in a loop. Move the output or exclude it from your content globs.",
+            output_path.display(),
+            source.pattern().display()
+        );
+    }
+}
+
+/// The generated CSS path: `output_path` relative to the manifest dir, or
+/// `assets/tailwind.css` by default.
+pub fn output_path(manifest_dir: &Path, output_path: Option<PathBuf>) -> PathBuf {
+    output_path.map_or_else(
+        || manifest_dir.join("assets").join("tailwind.css"),
+        |p| resolve_output_path(manifest_dir, &p),
+    )
+}
+
+fn resolve_output(manifest_dir: &Path, output_path: Option<PathBuf>) -> Result<PathBuf> {
+    let output_path = self::output_path(manifest_dir, output_path);
+    let parent = output_path
+        .parent()
+        .ok_or_else(|| anyhow!("tailwind output path has no parent"))?;
+    if !parent.exists() {
+        std::fs::create_dir_all(parent).context("failed to create tailwindcss output directory")?;
+    }
+    Ok(output_path)
 }
 
 fn resolve_output_path(manifest_dir: &Path, path: &Path) -> PathBuf {
@@ -386,3 +1322,216 @@ fn env_flag(name: &str) -> bool {
         "1" | "true" | "TRUE" | "yes" | "YES"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        TailwindCli, WINDOWS_SHIM_EXTENSIONS, clear_dir, find_shim, parse_installed_bin_name,
+        release_order, runs_through_cmd, sync_lock,
+    };
+    use crate::lockfile::{Lockfile, sha256_file};
+    use std::cell::Cell;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    fn lock_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("xilem-tw-sync-lock-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn lock_for(version: &str, entries: &[(&str, &str)]) -> Lockfile {
+        Lockfile {
+            version: version.to_string(),
+            sha256: entries
+                .iter()
+                .map(|(platform, hash)| ((*platform).to_string(), (*hash).to_string()))
+                .collect(),
+        }
+    }
+
+    fn sync(
+        dir: &Path,
+        lock: Option<&Lockfile>,
+        frozen: bool,
+        reinstalled: &str,
+    ) -> anyhow::Result<bool> {
+        let binary = dir.join("tailwindcss");
+        let called = Cell::new(false);
+        sync_lock(
+            dir,
+            lock,
+            "v4.1.5",
+            "tailwindcss-linux-x64".to_string(),
+            &binary,
+            frozen,
+            || {
+                called.set(true);
+                fs::write(&binary, reinstalled).map_err(Into::into)
+            },
+        )?;
+        Ok(called.get())
+    }
+
+    #[test]
+    fn parses_cached_binary_names() {
+        assert_eq!(
+            parse_installed_bin_name("tailwindcss-v4.1.5-x86_64-unknown-linux-gnu"),
+            Some(("v4.1.5".to_string(), "x86_64-unknown-linux-gnu".to_string()))
+        );
+        assert_eq!(
+            parse_installed_bin_name("tailwindcss-v4.0.0-beta.1-x86_64-pc-windows-msvc.exe"),
+            Some((
+                "v4.0.0-beta.1".to_string(),
+                "x86_64-pc-windows-msvc".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_installed_bin_name("tailwindcss-v4.1.5-aarch64-apple-darwin.123.tmp"),
+            None
+        );
+        assert_eq!(parse_installed_bin_name("latest-tag"), None);
+    }
+
+    #[test]
+    fn orders_release_tags() {
+        let mut tags = ["v4.1.10", "v3.4.17", "v4.0.0-beta.1", "v4.1.9", "v4.0.0"];
+        tags.sort_by_key(|tag| release_order(tag));
+        assert_eq!(
+            tags,
+            ["v3.4.17", "v4.0.0-beta.1", "v4.0.0", "v4.1.9", "v4.1.10"]
+        );
+    }
+
+    #[test]
+    fn frozen_without_a_lock_fails() {
+        let dir = lock_dir("frozen");
+        fs::write(dir.join("tailwindcss"), "good").unwrap();
+        let err = sync(&dir, None, true, "good").unwrap_err();
+        assert!(err.to_string().contains("--frozen"), "{err}");
+        assert_eq!(Lockfile::read(&dir).unwrap(), None);
+
+        assert!(!sync(&dir, None, false, "good").unwrap());
+        let hash = sha256_file(&dir.join("tailwindcss")).unwrap();
+        assert_eq!(
+            Lockfile::read(&dir).unwrap(),
+            Some(lock_for("v4.1.5", &[("tailwindcss-linux-x64", &hash)]))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn mismatched_binary_is_downloaded_again() {
+        let dir = lock_dir("mismatch");
+        let binary = dir.join("tailwindcss");
+        fs::write(&binary, "good").unwrap();
+        let lock = lock_for(
+            "v4.1.5",
+            &[("tailwindcss-linux-x64", &sha256_file(&binary).unwrap())],
+        );
+
+        fs::write(&binary, "corrupt").unwrap();
+        assert!(sync(&dir, Some(&lock), true, "good").unwrap());
+        assert_eq!(fs::read_to_string(&binary).unwrap(), "good");
+        // The lock already matched, so even `--frozen` leaves it unwritten.
+        assert_eq!(Lockfile::read(&dir).unwrap(), None);
+
+        fs::write(&binary, "corrupt").unwrap();
+        let err = sync(&dir, Some(&lock), false, "still corrupt").unwrap_err();
+        assert!(err.to_string().contains("expects"), "{err}");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn new_platform_is_added_to_the_lock() {
+        let dir = lock_dir("platform");
+        fs::write(dir.join("tailwindcss"), "good").unwrap();
+        let lock = lock_for("v4.1.5", &[("tailwindcss-macos-arm64", "0123")]);
+
+        let err = sync(&dir, Some(&lock), true, "good").unwrap_err();
+        assert!(err.to_string().contains("--frozen"), "{err}");
+
+        assert!(!sync(&dir, Some(&lock), false, "good").unwrap());
+        let hash = sha256_file(&dir.join("tailwindcss")).unwrap();
+        assert_eq!(
+            Lockfile::read(&dir).unwrap(),
+            Some(lock_for(
+                "v4.1.5",
+                &[
+                    ("tailwindcss-linux-x64", &hash),
+                    ("tailwindcss-macos-arm64", "0123")
+                ]
+            ))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn locked_version_never_writes_the_lock() {
+        let dir = lock_dir("read-only");
+        let latest = TailwindCli::latest();
+        assert_eq!(
+            latest.locked(&dir).unwrap().version(),
+            TailwindCli::LATEST_TAG
+        );
+        assert!(!Lockfile::path(&dir).exists());
+
+        lock_for("v4.1.5", &[]).write(&dir).unwrap();
+        let before = fs::read_to_string(Lockfile::path(&dir)).unwrap();
+        assert_eq!(latest.locked(&dir).unwrap().version(), "v4.1.5");
+        let pinned = TailwindCli::new("v3.4.17".to_string());
+        assert_eq!(pinned.locked(&dir).unwrap().version(), "v3.4.17");
+        assert_eq!(fs::read_to_string(Lockfile::path(&dir)).unwrap(), before);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn clearing_removes_binaries_and_partial_downloads() {
+        let dir = lock_dir("clear");
+        fs::write(dir.join("tailwindcss-v4.1.5-linux-x64"), "binary").unwrap();
+        fs::create_dir_all(dir.join("download.partial")).unwrap();
+        fs::write(dir.join("download.partial").join("chunk"), "partial").unwrap();
+
+        let removed = clear_dir(&dir).unwrap();
+        assert_eq!(
+            removed,
+            [
+                dir.join("download.partial"),
+                dir.join("tailwindcss-v4.1.5-linux-x64"),
+            ]
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir(&dir).unwrap();
+        assert!(clear_dir(&dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn windows_shims_are_tried_in_order() {
+        let mut tried = Vec::new();
+        let found = find_shim(WINDOWS_SHIM_EXTENSIONS, |name| {
+            tried.push(name.to_string());
+            (name != "tailwindcss.cmd").then(|| PathBuf::from(name))
+        });
+        assert_eq!(found, Some(PathBuf::from("tailwindcss.bat")));
+        assert_eq!(tried, ["tailwindcss.cmd", "tailwindcss.bat"]);
+        assert_eq!(find_shim(WINDOWS_SHIM_EXTENSIONS, |_| None), None);
+        assert_eq!(find_shim(&[], |name| Some(PathBuf::from(name))), None);
+    }
+
+    #[test]
+    fn only_batch_shims_on_windows_run_through_cmd() {
+        assert!(runs_through_cmd(Path::new(r"C:\npm\tailwindcss.cmd"), true));
+        assert!(runs_through_cmd(Path::new(r"C:\npm\tailwindcss.BAT"), true));
+        assert!(!runs_through_cmd(
+            Path::new(r"C:\npm\tailwindcss.exe"),
+            true
+        ));
+        assert!(!runs_through_cmd(Path::new("tailwindcss"), true));
+        assert!(!runs_through_cmd(
+            Path::new("/usr/bin/tailwindcss.cmd"),
+            false
+        ));
+    }
+}
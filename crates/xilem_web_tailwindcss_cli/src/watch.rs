@@ -0,0 +1,233 @@
+use std::collections::{BTreeSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::sources;
+
+/// How the first build of a supervised watch happens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InitialBuild {
+    /// Let `tailwindcss --watch` do its own initial build.
+    #[default]
+    Tailwind,
+    /// Run a one-off build before starting the watcher.
+    Force,
+    /// Start tailwind only once a content file or the input changes.
+    Skip,
+}
+
+impl InitialBuild {
+    pub fn from_flags(force: bool, skip: bool) -> Self {
+        if force {
+            Self::Force
+        } else if skip {
+            Self::Skip
+        } else {
+            Self::Tailwind
+        }
+    }
+}
+
+/// Settings for a supervised `watch`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WatchOptions {
+    pub minify: bool,
+    pub initial_build: InitialBuild,
+    /// Clear the terminal before the output of each rebuild.
+    pub clear: bool,
+}
+
+/// Number of recent rebuilds the rolling average covers.
+const WINDOW: usize = 10;
+
+/// Rebuild timings reported by a supervised `tailwindcss --watch`.
+#[derive(Debug, Default)]
+pub struct BuildStats {
+    recent: VecDeque<Duration>,
+    count: usize,
+    total: Duration,
+}
+
+impl BuildStats {
+    pub fn record(&mut self, duration: Duration) {
+        if self.recent.len() == WINDOW {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(duration);
+        self.count += 1;
+        self.total += duration;
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Time spent building over the whole session.
+    pub fn total(&self) -> Duration {
+        self.total
+    }
+
+    /// The average over the whole session, unlike [`Self::rolling_average`].
+    pub fn average(&self) -> Option<Duration> {
+        let count = u32::try_from(self.count).ok().filter(|count| *count > 0)?;
+        Some(self.total / count)
+    }
+
+    pub fn rolling_average(&self) -> Option<Duration> {
+        let total: Duration = self.recent.iter().sum();
+        let len = u32::try_from(self.recent.len())
+            .ok()
+            .filter(|len| *len > 0)?;
+        Some(total / len)
+    }
+
+    /// Whether a periodic average should be logged after the latest build.
+    pub fn should_report(&self) -> bool {
+        self.count > 0 && self.count % WINDOW == 0
+    }
+}
+
+/// Parse tailwind's `Done in 412ms` / `Done in 1.2s` completion line.
+pub fn parse_build_duration(line: &str) -> Option<Duration> {
+    let rest = line.trim().strip_prefix("Done in ")?;
+    let rest = rest.trim_end_matches('.');
+    if let Some(ms) = rest.strip_suffix("ms") {
+        return ms
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .map(|ms| Duration::from_secs_f64(ms / 1000.0));
+    }
+    rest.strip_suffix('s')?
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .map(Duration::from_secs_f64)
+}
+
+/// The state a supervised watch restarts tailwind on: `Cargo.toml` and the
+/// set of files matched by the content sources.
+///
+/// Tailwind picks up edits to files it already knows, but can miss files
+/// created after it started, e.g. a new component module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchSnapshot {
+    manifest: Option<SystemTime>,
+    files: BTreeSet<PathBuf>,
+    /// The newest modification time among `files`.
+    newest: Option<SystemTime>,
+}
+
+impl WatchSnapshot {
+    pub fn capture(manifest_dir: &Path, input_path: &Path) -> Self {
+        let files = sources::content_files(manifest_dir, input_path);
+        let manifest = modified(&manifest_dir.join("Cargo.toml"));
+        let newest = files.iter().filter_map(|path| modified(path)).max();
+        Self {
+            manifest,
+            files,
+            newest,
+        }
+    }
+
+    /// Whether a content file was added, removed or edited since `self`.
+    pub fn content_changed(&self, next: &Self) -> bool {
+        self.files != next.files || self.newest != next.newest
+    }
+
+    /// Why tailwind should restart after moving from `self` to `next`, if at all.
+    pub fn restart_reason(&self, next: &Self) -> Option<String> {
+        if self.manifest != next.manifest {
+            return Some("Cargo.toml changed".to_string());
+        }
+        next.files
+            .difference(&self.files)
+            .next()
+            .map(|path| format!("New content file {}", path.display()))
+    }
+}
+
+/// The newest modification time among the content files and the input.
+pub fn newest_modification(manifest_dir: &Path, input_path: &Path) -> Option<SystemTime> {
+    sources::content_files(manifest_dir, input_path)
+        .iter()
+        .chain(std::iter::once(&input_path.to_path_buf()))
+        .filter_map(|path| modified(path))
+        .max()
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildStats, WatchSnapshot};
+    use std::fs;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn session_totals_outlive_the_rolling_window() {
+        let mut stats = BuildStats::default();
+        assert_eq!(stats.average(), None);
+        for ms in 1..=20 {
+            stats.record(Duration::from_millis(ms));
+        }
+        assert_eq!(stats.count(), 20);
+        assert_eq!(stats.total(), Duration::from_millis(210));
+        assert_eq!(
+            stats.average(),
+            Some(Duration::from_millis(10) + Duration::from_micros(500))
+        );
+        assert_eq!(
+            stats.rolling_average(),
+            Some(Duration::from_millis(15) + Duration::from_micros(500))
+        );
+    }
+
+    #[test]
+    fn new_rust_file_and_manifest_edit_restart_watch() {
+        let dir = std::env::temp_dir().join(format!("xilem-tw-watch-{}", std::process::id()));
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("Cargo.toml"), "[package]\n").unwrap();
+        fs::write(dir.join("src/main.rs"), "tw!(\"p-4\")").unwrap();
+        let input = dir.join("tailwind.css");
+
+        let before = WatchSnapshot::capture(&dir, &input);
+        assert_eq!(before.restart_reason(&before.clone()), None);
+        assert!(!before.content_changed(&before.clone()));
+
+        fs::write(dir.join("src/card.rs"), "tw!(\"bg-fuchsia-700\")").unwrap();
+        let after = WatchSnapshot::capture(&dir, &input);
+        let reason = after_reason(&before, &after);
+        assert!(reason.ends_with("card.rs"), "{reason}");
+        assert!(before.content_changed(&after));
+
+        let card = fs::File::options()
+            .write(true)
+            .open(dir.join("src/card.rs"))
+            .unwrap();
+        card.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let touched = WatchSnapshot::capture(&dir, &input);
+        assert_eq!(after.restart_reason(&touched), None);
+        assert!(after.content_changed(&touched));
+
+        let manifest = fs::File::options()
+            .write(true)
+            .open(dir.join("Cargo.toml"))
+            .unwrap();
+        manifest
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let edited = WatchSnapshot::capture(&dir, &input);
+        assert_eq!(after_reason(&after, &edited), "Cargo.toml changed");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    fn after_reason(before: &WatchSnapshot, after: &WatchSnapshot) -> String {
+        before.restart_reason(after).expect("expected a restart")
+    }
+}
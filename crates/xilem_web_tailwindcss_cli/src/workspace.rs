@@ -0,0 +1,156 @@
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item};
+
+/// The crates to build when `--manifest-path` is a glob or `--workspace` is
+/// set, or `None` for a single-crate run.
+pub fn select(manifest_path: Option<&Path>, workspace: bool) -> Result<Option<Vec<PathBuf>>> {
+    let path = manifest_path.unwrap_or(Path::new("."));
+    if is_glob(path) {
+        glob_members(path).map(Some)
+    } else if workspace {
+        workspace_members(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+pub fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
+
+/// Crate directories selected by a `--manifest-path` glob such as
+/// `crates/*/Cargo.toml` (or `crates/*`).
+fn glob_members(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.to_string_lossy();
+    let mut dirs = glob::glob(&pattern)
+        .with_context(|| format!("invalid manifest glob {pattern}"))?
+        .filter_map(Result::ok)
+        .filter_map(|path| crate_dir(&path))
+        .collect::<Vec<_>>();
+    dirs.sort();
+    dirs.dedup();
+    if dirs.is_empty() {
+        return Err(anyhow!("manifest glob {pattern} matched no crates"));
+    }
+    Ok(dirs)
+}
+
+/// Members of the workspace containing `start`, including the root package.
+fn workspace_members(start: &Path) -> Result<Vec<PathBuf>> {
+    let (root, doc) = find_workspace_root(start)?;
+    let workspace = doc
+        .get("workspace")
+        .ok_or_else(|| anyhow!("{} has no [workspace] table", root.display()))?;
+    let excluded = string_array(workspace.get("exclude"))
+        .into_iter()
+        .map(|path| root.join(path))
+        .collect::<Vec<_>>();
+
+    let mut dirs = Vec::new();
+    if doc.get("package").is_some() {
+        dirs.push(root.clone());
+    }
+    for member in string_array(workspace.get("members")) {
+        let pattern = root.join(member);
+        let pattern = pattern.to_string_lossy();
+        let matches =
+            glob::glob(&pattern).with_context(|| format!("invalid workspace member {pattern}"))?;
+        dirs.extend(
+            matches
+                .filter_map(Result::ok)
+                .filter(|dir| !excluded.contains(dir))
+                .filter_map(|path| crate_dir(&path)),
+        );
+    }
+    dirs.sort();
+    dirs.dedup();
+    Ok(dirs)
+}
+
+fn find_workspace_root(start: &Path) -> Result<(PathBuf, DocumentMut)> {
+    let start = fs::canonicalize(start)
+        .with_context(|| format!("failed to resolve {}", start.display()))?;
+    for dir in start.ancestors() {
+        let manifest = dir.join("Cargo.toml");
+        let Ok(contents) = fs::read_to_string(&manifest) else {
+            continue;
+        };
+        let doc: DocumentMut = contents
+            .parse()
+            .with_context(|| format!("failed to parse {}", manifest.display()))?;
+        if doc.get("workspace").is_some() {
+            return Ok((dir.to_path_buf(), doc));
+        }
+    }
+    Err(anyhow!(
+        "no workspace Cargo.toml found above {}",
+        start.display()
+    ))
+}
+
+fn string_array(item: Option<&Item>) -> Vec<String> {
+    item.and_then(Item::as_array)
+        .map(|array| {
+            array
+                .iter()
+                .filter_map(|value| value.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The crate directory for a `Cargo.toml` path or a directory holding one.
+fn crate_dir(path: &Path) -> Option<PathBuf> {
+    let dir = if path.is_dir() { path } else { path.parent()? };
+    if !dir.join("Cargo.toml").is_file() {
+        return None;
+    }
+    fs::canonicalize(dir).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::select;
+    use std::fs;
+
+    #[test]
+    fn workspace_members_honour_globs_and_exclude() {
+        let dir = std::env::temp_dir().join(format!("xilem-tw-workspace-{}", std::process::id()));
+        for name in ["app", "site", "scratch"] {
+            fs::create_dir_all(dir.join("crates").join(name)).unwrap();
+            fs::write(
+                dir.join("crates").join(name).join("Cargo.toml"),
+                "[package]\n",
+            )
+            .unwrap();
+        }
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\n[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/scratch\"]\n",
+        )
+        .unwrap();
+        let root = fs::canonicalize(&dir).unwrap();
+
+        let members = select(Some(&dir), true).unwrap().unwrap();
+        assert_eq!(
+            members,
+            [
+                root.clone(),
+                root.join("crates/app"),
+                root.join("crates/site")
+            ]
+        );
+
+        let pattern = dir.join("crates/s*/Cargo.toml");
+        let matched = select(Some(&pattern), false).unwrap().unwrap();
+        assert_eq!(
+            matched,
+            [root.join("crates/scratch"), root.join("crates/site")]
+        );
+
+        assert_eq!(select(Some(&dir), false).unwrap(), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}